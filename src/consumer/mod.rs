@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+
+use crate::{GameResult, WordGameError};
+
+pub const ROLL_CALLBACK_IX_NAME: &str = "global:consume_word_game_roll";
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RollCallback {
+    pub game: Pubkey,
+    pub request_id: [u8; 32],
+    pub slot: u64,
+    pub die_faces: [u8; 2],
+    pub outcome: u8,
+    pub point: u8,
+    pub result: GameResult,
+    pub settled: bool,
+}
+
+impl RollCallback {
+    pub const SPACE: usize = 32 + 32 + 8 + 2 + 1 + 1 + 1 + 1;
+}
+
+pub fn roll_callback_discriminator() -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(ROLL_CALLBACK_IX_NAME.as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+pub fn encode_roll_callback(callback: &RollCallback) -> Result<Vec<u8>> {
+    let mut data = roll_callback_discriminator().to_vec();
+    callback.serialize(&mut data)?;
+    Ok(data)
+}
+
+pub fn decode_roll_callback(data: &[u8]) -> Result<RollCallback> {
+    require!(
+        data.len() >= 8 && data[..8] == roll_callback_discriminator(),
+        WordGameError::InvalidConsumerCallback
+    );
+    RollCallback::try_from_slice(&data[8..])
+        .map_err(|_| error!(WordGameError::InvalidConsumerCallback))
+}
+
+pub fn verify_callback_signer(game: &AccountInfo, callback: &RollCallback) -> Result<()> {
+    require!(game.is_signer, WordGameError::InvalidConsumerCallback);
+    require_keys_eq!(
+        *game.owner,
+        crate::ID,
+        WordGameError::InvalidConsumerCallback
+    );
+    require_keys_eq!(
+        *game.key,
+        callback.game,
+        WordGameError::InvalidConsumerCallback
+    );
+    Ok(())
+}
@@ -1,4 +1,8 @@
-use crate::types::{ContributorRecord, WordGameAccount, MAX_CONTRIBUTORS};
+use crate::types::{
+    BeneficiaryPayoutPreview, BeneficiaryRecord, ClaimReceipt, ClosePreview,
+    ContributorPayoutPreview, ContributorRecord, WordGameAccount, MAX_BENEFICIARIES,
+    MAX_CONTRIBUTORS,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -21,6 +25,29 @@ pub enum ProgramError {
     DoubleWithdraw,
     #[error("math overflow")]
     MathOverflow,
+    #[error("beneficiary shares must be non-zero and sum to 10000 bps")]
+    InvalidBeneficiaryShares,
+    #[error("beneficiary is listed more than once")]
+    DuplicateBeneficiary,
+    #[error("beneficiary is not registered")]
+    BeneficiaryNotFound,
+    #[error("beneficiary table is full")]
+    BeneficiaryTableFull,
+    #[error("caller may not claim for this contributor")]
+    UnauthorizedClaimant,
+    #[error("caller may not change this contributor's claim settings")]
+    UnauthorizedCaller,
+    #[error("only the owner may change the beneficiaries")]
+    UnauthorizedOwner,
+    #[error("the owner must remain a beneficiary")]
+    OwnerNotBeneficiary,
+    #[error("batch is empty")]
+    EmptyBatch,
+    #[error("batch entry {index} failed: {error}")]
+    BatchEntryFailed {
+        index: usize,
+        error: Box<ProgramError>,
+    },
 }
 
 pub type ProgramResult<T> = Result<T, ProgramError>;
@@ -32,6 +59,57 @@ pub fn initialize(owner: [u8; 32], treasury_bps: u16) -> ProgramResult<WordGameA
     Ok(WordGameAccount::new(owner, treasury_bps))
 }
 
+pub fn set_beneficiaries(
+    account: &mut WordGameAccount,
+    caller: [u8; 32],
+    beneficiaries: &[([u8; 32], u16)],
+) -> ProgramResult<()> {
+    if caller != account.owner {
+        return Err(ProgramError::UnauthorizedOwner);
+    }
+    if account.current_round.is_closed {
+        return Err(ProgramError::RoundAlreadyClosed);
+    }
+    if beneficiaries.is_empty() {
+        return Err(ProgramError::InvalidBeneficiaryShares);
+    }
+    if beneficiaries.len() > MAX_BENEFICIARIES {
+        return Err(ProgramError::BeneficiaryTableFull);
+    }
+
+    let mut total_bps: u32 = 0;
+    for (index, (beneficiary, share_bps)) in beneficiaries.iter().enumerate() {
+        if *share_bps == 0 {
+            return Err(ProgramError::InvalidBeneficiaryShares);
+        }
+        if beneficiaries[..index]
+            .iter()
+            .any(|(other, _)| other == beneficiary)
+        {
+            return Err(ProgramError::DuplicateBeneficiary);
+        }
+        total_bps += *share_bps as u32;
+    }
+    if total_bps != 10_000 {
+        return Err(ProgramError::InvalidBeneficiaryShares);
+    }
+    // owner_withdraw draws from the owner's own row, so the owner always keeps one.
+    if !beneficiaries
+        .iter()
+        .any(|(beneficiary, _)| *beneficiary == account.owner)
+    {
+        return Err(ProgramError::OwnerNotBeneficiary);
+    }
+
+    let mut table = vec![BeneficiaryRecord::empty(); MAX_BENEFICIARIES];
+    for (slot, (beneficiary, share_bps)) in table.iter_mut().zip(beneficiaries) {
+        *slot = BeneficiaryRecord::new(*beneficiary, *share_bps);
+    }
+    account.beneficiaries = table;
+
+    Ok(())
+}
+
 pub fn contribute(
     account: &mut WordGameAccount,
     contributor: [u8; 32],
@@ -44,49 +122,94 @@ pub fn contribute(
         return Err(ProgramError::RoundAlreadyClosed);
     }
 
-    let idx = find_or_create_contributor_slot(&mut account.contributors, contributor)?;
-    let record = &mut account.contributors[idx];
-
-    record.total_contributed_lamports = record
-        .total_contributed_lamports
-        .checked_add(lamports)
-        .ok_or(ProgramError::MathOverflow)?;
-
-    account.current_round.total_round_contributions = account
+    let (slot, total_contributed_lamports) =
+        validate_contribution(&account.contributors, contributor, lamports)?;
+    let total_round_contributions = account
         .current_round
         .total_round_contributions
         .checked_add(lamports)
         .ok_or(ProgramError::MathOverflow)?;
 
+    apply_contribution(
+        &mut account.contributors,
+        slot,
+        contributor,
+        total_contributed_lamports,
+    );
+    account.current_round.total_round_contributions = total_round_contributions;
+
     Ok(())
 }
 
+pub fn contribute_batch(
+    account: &mut WordGameAccount,
+    entries: &[([u8; 32], u64)],
+) -> ProgramResult<Vec<u64>> {
+    if entries.is_empty() {
+        return Err(ProgramError::EmptyBatch);
+    }
+    if account.current_round.is_closed {
+        return Err(ProgramError::RoundAlreadyClosed);
+    }
+
+    let mut contributors = account.contributors.clone();
+    let mut total_round_contributions = account.current_round.total_round_contributions;
+    let mut totals = Vec::with_capacity(entries.len());
+
+    for (index, (contributor, lamports)) in entries.iter().enumerate() {
+        let staged = (|| -> ProgramResult<(ContributorSlot, u64, u64)> {
+            if *lamports == 0 {
+                return Err(ProgramError::InvalidAmount);
+            }
+            let (slot, total) = validate_contribution(&contributors, *contributor, *lamports)?;
+            let round_total = total_round_contributions
+                .checked_add(*lamports)
+                .ok_or(ProgramError::MathOverflow)?;
+            Ok((slot, total, round_total))
+        })();
+        let (slot, total, round_total) =
+            staged.map_err(|error| batch_entry_failed(index, error))?;
+
+        apply_contribution(&mut contributors, slot, *contributor, total);
+        total_round_contributions = round_total;
+        totals.push(total);
+    }
+
+    account.contributors = contributors;
+    account.current_round.total_round_contributions = total_round_contributions;
+
+    Ok(totals)
+}
+
 pub fn close_round(account: &mut WordGameAccount) -> ProgramResult<()> {
     if account.current_round.is_closed {
         return Err(ProgramError::RoundAlreadyClosed);
     }
 
-    let treasury_cut = ((account.current_round.total_round_contributions as u128)
-        .checked_mul(account.treasury_bps as u128)
-        .ok_or(ProgramError::MathOverflow)?)
-        / 10_000u128;
-    let treasury_cut_u64 = u64::try_from(treasury_cut).map_err(|_| ProgramError::MathOverflow)?;
+    let settlement = compute_round_settlement(account)?;
 
-    account.current_round.owner_withdrawable_lamports = treasury_cut_u64;
-    account.current_round.total_round_claimable = account
-        .current_round
-        .total_round_contributions
-        .checked_sub(treasury_cut_u64)
-        .ok_or(ProgramError::MathOverflow)?;
+    account.current_round.owner_withdrawable_lamports = settlement.treasury_cut;
+    account.current_round.total_round_claimable = settlement.total_claimable;
 
-    for record in &mut account.contributors {
+    for (record, share) in account
+        .beneficiaries
+        .iter_mut()
+        .zip(settlement.beneficiary_shares)
+    {
         if record.is_initialized {
-            let claimable = ((record.total_contributed_lamports as u128)
-                .checked_mul((10_000u16 - account.treasury_bps) as u128)
-                .ok_or(ProgramError::MathOverflow)?)
-                / 10_000u128;
-            record.claimable_lamports =
-                u64::try_from(claimable).map_err(|_| ProgramError::MathOverflow)?;
+            record.withdrawable_lamports = share;
+            record.withdrawn_lamports = 0;
+            record.has_withdrawn_current_round = false;
+        }
+    }
+
+    for (record, claimable) in account
+        .contributors
+        .iter_mut()
+        .zip(settlement.contributor_claimables)
+    {
+        if record.is_initialized {
+            record.claimable_lamports = claimable;
             record.claimed_lamports = 0;
             record.has_claimed_current_round = false;
         }
@@ -96,48 +219,206 @@ pub fn close_round(account: &mut WordGameAccount) -> ProgramResult<()> {
     Ok(())
 }
 
-pub fn claim(account: &mut WordGameAccount, contributor: [u8; 32]) -> ProgramResult<u64> {
-    if !account.current_round.is_closed {
-        return Err(ProgramError::RoundStillOpen);
+pub fn preview_close(account: &WordGameAccount) -> ProgramResult<ClosePreview> {
+    if account.current_round.is_closed {
+        return Err(ProgramError::RoundAlreadyClosed);
     }
 
-    let idx = find_contributor_slot(&account.contributors, contributor)
-        .ok_or(ProgramError::ContributorNotFound)?;
-    let record = &mut account.contributors[idx];
+    let settlement = compute_round_settlement(account)?;
 
-    if record.has_claimed_current_round {
-        return Err(ProgramError::DoubleClaim);
+    let mut contributors = Vec::new();
+    let mut projected_claims: u64 = 0;
+    for (record, claimable) in account
+        .contributors
+        .iter()
+        .zip(settlement.contributor_claimables)
+    {
+        if record.is_initialized {
+            projected_claims = projected_claims
+                .checked_add(claimable)
+                .ok_or(ProgramError::MathOverflow)?;
+            contributors.push(ContributorPayoutPreview {
+                contributor: record.contributor,
+                total_contributed_lamports: record.total_contributed_lamports,
+                claimable_lamports: claimable,
+            });
+        }
     }
 
-    let amount = record.claimable_lamports;
-    if amount == 0 {
-        return Err(ProgramError::InvalidAmount);
-    }
+    let beneficiaries = account
+        .beneficiaries
+        .iter()
+        .zip(settlement.beneficiary_shares)
+        .filter(|(record, _)| record.is_initialized)
+        .map(|(record, share)| BeneficiaryPayoutPreview {
+            beneficiary: record.beneficiary,
+            withdrawable_lamports: share,
+        })
+        .collect();
+
+    let dust_lamports = settlement
+        .total_claimable
+        .checked_sub(projected_claims)
+        .ok_or(ProgramError::MathOverflow)?;
 
-    record.claimed_lamports = amount;
-    record.claimable_lamports = 0;
-    record.has_claimed_current_round = true;
+    Ok(ClosePreview {
+        round_id: account.current_round.round_id,
+        total_round_contributions: account.current_round.total_round_contributions,
+        treasury_cut_lamports: settlement.treasury_cut,
+        total_round_claimable: settlement.total_claimable,
+        dust_lamports,
+        contributors,
+        beneficiaries,
+    })
+}
 
-    account.current_round.total_round_claimed = account
+pub fn claim(account: &mut WordGameAccount, contributor: [u8; 32]) -> ProgramResult<u64> {
+    if !account.current_round.is_closed {
+        return Err(ProgramError::RoundStillOpen);
+    }
+
+    let (idx, amount) = validate_claim(&account.contributors, contributor)?;
+    let total_round_claimed = account
         .current_round
         .total_round_claimed
         .checked_add(amount)
         .ok_or(ProgramError::MathOverflow)?;
 
+    apply_claim(&mut account.contributors[idx], amount);
+    account.current_round.total_round_claimed = total_round_claimed;
+
     Ok(amount)
 }
 
+pub fn claim_batch(
+    account: &mut WordGameAccount,
+    contributors: &[[u8; 32]],
+) -> ProgramResult<Vec<ClaimReceipt>> {
+    if contributors.is_empty() {
+        return Err(ProgramError::EmptyBatch);
+    }
+    if !account.current_round.is_closed {
+        return Err(ProgramError::RoundStillOpen);
+    }
+
+    let mut records = account.contributors.clone();
+    let mut total_round_claimed = account.current_round.total_round_claimed;
+    let mut receipts = Vec::with_capacity(contributors.len());
+
+    for (index, contributor) in contributors.iter().enumerate() {
+        let staged = (|| -> ProgramResult<(usize, u64, u64)> {
+            let (idx, amount) = validate_claim(&records, *contributor)?;
+            let claimed = total_round_claimed
+                .checked_add(amount)
+                .ok_or(ProgramError::MathOverflow)?;
+            Ok((idx, amount, claimed))
+        })();
+        let (idx, amount, claimed) = staged.map_err(|error| batch_entry_failed(index, error))?;
+
+        apply_claim(&mut records[idx], amount);
+        total_round_claimed = claimed;
+        receipts.push(claim_receipt(&records[idx], amount));
+    }
+
+    account.contributors = records;
+    account.current_round.total_round_claimed = total_round_claimed;
+
+    Ok(receipts)
+}
+
+pub fn claim_for(
+    account: &mut WordGameAccount,
+    caller: [u8; 32],
+    contributor: [u8; 32],
+) -> ProgramResult<ClaimReceipt> {
+    let idx = find_contributor_slot(&account.contributors, contributor)
+        .ok_or(ProgramError::ContributorNotFound)?;
+    let record = &account.contributors[idx];
+
+    let is_delegate = record.claim_delegate != [0u8; 32] && record.claim_delegate == caller;
+    if caller != contributor && !is_delegate {
+        return Err(ProgramError::UnauthorizedClaimant);
+    }
+
+    let lamports = claim(account, contributor)?;
+    Ok(claim_receipt(&account.contributors[idx], lamports))
+}
+
+pub fn set_payout_address(
+    account: &mut WordGameAccount,
+    caller: [u8; 32],
+    contributor: [u8; 32],
+    payout_address: [u8; 32],
+) -> ProgramResult<()> {
+    if caller != contributor {
+        return Err(ProgramError::UnauthorizedCaller);
+    }
+    let idx = find_contributor_slot(&account.contributors, contributor)
+        .ok_or(ProgramError::ContributorNotFound)?;
+    account.contributors[idx].payout_address = payout_address;
+    Ok(())
+}
+
+pub fn set_claim_delegate(
+    account: &mut WordGameAccount,
+    caller: [u8; 32],
+    contributor: [u8; 32],
+    delegate: [u8; 32],
+) -> ProgramResult<()> {
+    if caller != contributor {
+        return Err(ProgramError::UnauthorizedCaller);
+    }
+    let idx = find_contributor_slot(&account.contributors, contributor)
+        .ok_or(ProgramError::ContributorNotFound)?;
+    account.contributors[idx].claim_delegate = delegate;
+    Ok(())
+}
+
+pub fn payout_address(account: &WordGameAccount, contributor: [u8; 32]) -> ProgramResult<[u8; 32]> {
+    let idx = find_contributor_slot(&account.contributors, contributor)
+        .ok_or(ProgramError::ContributorNotFound)?;
+    Ok(resolve_payout_address(&account.contributors[idx]))
+}
+
 pub fn owner_withdraw(account: &mut WordGameAccount) -> ProgramResult<u64> {
+    let owner = account.owner;
+    beneficiary_withdraw(account, owner)
+}
+
+pub fn beneficiary_withdraw(
+    account: &mut WordGameAccount,
+    beneficiary: [u8; 32],
+) -> ProgramResult<u64> {
     if !account.current_round.is_closed {
         return Err(ProgramError::RoundStillOpen);
     }
-    if account.current_round.owner_has_withdrawn {
+
+    let idx = find_beneficiary_slot(&account.beneficiaries, beneficiary)
+        .ok_or(ProgramError::BeneficiaryNotFound)?;
+    let record = &account.beneficiaries[idx];
+
+    if record.has_withdrawn_current_round {
         return Err(ProgramError::DoubleWithdraw);
     }
 
-    let amount = account.current_round.owner_withdrawable_lamports;
-    account.current_round.owner_withdrawable_lamports = 0;
-    account.current_round.owner_has_withdrawn = true;
+    let amount = record.withdrawable_lamports;
+    let owner_withdrawable_lamports = account
+        .current_round
+        .owner_withdrawable_lamports
+        .checked_sub(amount)
+        .ok_or(ProgramError::MathOverflow)?;
+
+    let record = &mut account.beneficiaries[idx];
+    record.withdrawn_lamports = amount;
+    record.withdrawable_lamports = 0;
+    record.has_withdrawn_current_round = true;
+
+    account.current_round.owner_withdrawable_lamports = owner_withdrawable_lamports;
+    account.current_round.owner_has_withdrawn = account
+        .beneficiaries
+        .iter()
+        .all(|record| !record.is_initialized || record.has_withdrawn_current_round);
+
     Ok(amount)
 }
 
@@ -146,6 +427,12 @@ pub fn reset_round(account: &mut WordGameAccount) -> ProgramResult<()> {
         return Err(ProgramError::RoundStillOpen);
     }
 
+    let next_round = account
+        .current_round
+        .round_id
+        .checked_add(1)
+        .ok_or(ProgramError::MathOverflow)?;
+
     for record in &mut account.contributors {
         if record.is_initialized {
             record.total_contributed_lamports = 0;
@@ -155,16 +442,110 @@ pub fn reset_round(account: &mut WordGameAccount) -> ProgramResult<()> {
         }
     }
 
-    let next_round = account
-        .current_round
-        .round_id
-        .checked_add(1)
-        .ok_or(ProgramError::MathOverflow)?;
+    for record in &mut account.beneficiaries {
+        if record.is_initialized {
+            record.withdrawable_lamports = 0;
+            record.withdrawn_lamports = 0;
+            record.has_withdrawn_current_round = false;
+        }
+    }
+
     account.current_round = crate::types::RoundState::new(next_round);
 
     Ok(())
 }
 
+struct RoundSettlement {
+    treasury_cut: u64,
+    total_claimable: u64,
+    beneficiary_shares: Vec<u64>,
+    contributor_claimables: Vec<u64>,
+}
+
+fn compute_round_settlement(account: &WordGameAccount) -> ProgramResult<RoundSettlement> {
+    let contributor_bps = 10_000u16
+        .checked_sub(account.treasury_bps)
+        .ok_or(ProgramError::InvalidTreasuryBps)?;
+
+    let treasury_cut = ((account.current_round.total_round_contributions as u128)
+        .checked_mul(account.treasury_bps as u128)
+        .ok_or(ProgramError::MathOverflow)?)
+        / 10_000u128;
+    let treasury_cut = u64::try_from(treasury_cut).map_err(|_| ProgramError::MathOverflow)?;
+
+    let total_claimable = account
+        .current_round
+        .total_round_contributions
+        .checked_sub(treasury_cut)
+        .ok_or(ProgramError::MathOverflow)?;
+
+    let beneficiary_shares = split_treasury_cut(&account.beneficiaries, treasury_cut)?;
+
+    let mut contributor_claimables = vec![0u64; account.contributors.len()];
+    for (record, claimable) in account
+        .contributors
+        .iter()
+        .zip(contributor_claimables.iter_mut())
+    {
+        if record.is_initialized {
+            let amount = ((record.total_contributed_lamports as u128)
+                .checked_mul(contributor_bps as u128)
+                .ok_or(ProgramError::MathOverflow)?)
+                / 10_000u128;
+            *claimable = u64::try_from(amount).map_err(|_| ProgramError::MathOverflow)?;
+        }
+    }
+
+    Ok(RoundSettlement {
+        treasury_cut,
+        total_claimable,
+        beneficiary_shares,
+        contributor_claimables,
+    })
+}
+
+fn split_treasury_cut(
+    beneficiaries: &[BeneficiaryRecord],
+    treasury_cut: u64,
+) -> ProgramResult<Vec<u64>> {
+    let mut shares = vec![0u64; beneficiaries.len()];
+    let mut distributed: u64 = 0;
+    for (record, share) in beneficiaries.iter().zip(shares.iter_mut()) {
+        if record.is_initialized {
+            let amount = ((treasury_cut as u128)
+                .checked_mul(record.share_bps as u128)
+                .ok_or(ProgramError::MathOverflow)?)
+                / 10_000u128;
+            *share = u64::try_from(amount).map_err(|_| ProgramError::MathOverflow)?;
+            distributed = distributed
+                .checked_add(*share)
+                .ok_or(ProgramError::MathOverflow)?;
+        }
+    }
+
+    let dust = treasury_cut
+        .checked_sub(distributed)
+        .ok_or(ProgramError::MathOverflow)?;
+    let first = beneficiaries
+        .iter()
+        .position(|record| record.is_initialized)
+        .ok_or(ProgramError::BeneficiaryNotFound)?;
+    shares[first] = shares[first]
+        .checked_add(dust)
+        .ok_or(ProgramError::MathOverflow)?;
+
+    Ok(shares)
+}
+
+fn find_beneficiary_slot(
+    beneficiaries: &[BeneficiaryRecord],
+    beneficiary: [u8; 32],
+) -> Option<usize> {
+    beneficiaries
+        .iter()
+        .position(|record| record.is_initialized && record.beneficiary == beneficiary)
+}
+
 fn find_contributor_slot(
     contributors: &[ContributorRecord],
     contributor: [u8; 32],
@@ -174,31 +555,112 @@ fn find_contributor_slot(
         .position(|record| record.is_initialized && record.contributor == contributor)
 }
 
-fn find_or_create_contributor_slot(
-    contributors: &mut [ContributorRecord],
+#[derive(Clone, Copy)]
+enum ContributorSlot {
+    Existing(usize),
+    Vacant(usize),
+}
+
+fn locate_contributor_slot(
+    contributors: &[ContributorRecord],
     contributor: [u8; 32],
-) -> ProgramResult<usize> {
+) -> ProgramResult<ContributorSlot> {
     if let Some(index) = find_contributor_slot(contributors, contributor) {
-        return Ok(index);
+        return Ok(ContributorSlot::Existing(index));
     }
 
-    let index = contributors
+    contributors
         .iter()
+        .take(MAX_CONTRIBUTORS)
         .position(|record| !record.is_initialized)
-        .ok_or(ProgramError::ContributorTableFull)?;
+        .map(ContributorSlot::Vacant)
+        .ok_or(ProgramError::ContributorTableFull)
+}
 
-    contributors[index].is_initialized = true;
-    contributors[index].contributor = contributor;
-    contributors[index].total_contributed_lamports = 0;
-    contributors[index].claimable_lamports = 0;
-    contributors[index].claimed_lamports = 0;
-    contributors[index].has_claimed_current_round = false;
+fn validate_contribution(
+    contributors: &[ContributorRecord],
+    contributor: [u8; 32],
+    lamports: u64,
+) -> ProgramResult<(ContributorSlot, u64)> {
+    let slot = locate_contributor_slot(contributors, contributor)?;
+    let current_total = match slot {
+        ContributorSlot::Existing(idx) => contributors[idx].total_contributed_lamports,
+        ContributorSlot::Vacant(_) => 0,
+    };
+    let total = current_total
+        .checked_add(lamports)
+        .ok_or(ProgramError::MathOverflow)?;
+
+    Ok((slot, total))
+}
+
+fn apply_contribution(
+    contributors: &mut [ContributorRecord],
+    slot: ContributorSlot,
+    contributor: [u8; 32],
+    total_contributed_lamports: u64,
+) {
+    let idx = match slot {
+        ContributorSlot::Existing(idx) => idx,
+        ContributorSlot::Vacant(idx) => {
+            contributors[idx] = ContributorRecord {
+                contributor,
+                is_initialized: true,
+                ..ContributorRecord::empty()
+            };
+            idx
+        }
+    };
+    contributors[idx].total_contributed_lamports = total_contributed_lamports;
+}
 
-    if index >= MAX_CONTRIBUTORS {
-        return Err(ProgramError::ContributorTableFull);
+fn validate_claim(
+    contributors: &[ContributorRecord],
+    contributor: [u8; 32],
+) -> ProgramResult<(usize, u64)> {
+    let idx = find_contributor_slot(contributors, contributor)
+        .ok_or(ProgramError::ContributorNotFound)?;
+    let record = &contributors[idx];
+
+    if record.has_claimed_current_round {
+        return Err(ProgramError::DoubleClaim);
+    }
+
+    let amount = record.claimable_lamports;
+    if amount == 0 {
+        return Err(ProgramError::InvalidAmount);
     }
 
-    Ok(index)
+    Ok((idx, amount))
+}
+
+fn apply_claim(record: &mut ContributorRecord, amount: u64) {
+    record.claimed_lamports = amount;
+    record.claimable_lamports = 0;
+    record.has_claimed_current_round = true;
+}
+
+fn claim_receipt(record: &ContributorRecord, lamports: u64) -> ClaimReceipt {
+    ClaimReceipt {
+        contributor: record.contributor,
+        recipient: resolve_payout_address(record),
+        lamports,
+    }
+}
+
+fn resolve_payout_address(record: &ContributorRecord) -> [u8; 32] {
+    if record.payout_address == [0u8; 32] {
+        record.contributor
+    } else {
+        record.payout_address
+    }
+}
+
+fn batch_entry_failed(index: usize, error: ProgramError) -> ProgramError {
+    ProgramError::BatchEntryFailed {
+        index,
+        error: Box::new(error),
+    }
 }
 
 #[cfg(test)]
@@ -272,4 +734,251 @@ mod tests {
         let claim_result = claim(&mut account, key(10));
         assert_eq!(claim_result, Err(ProgramError::RoundStillOpen));
     }
+
+    #[test]
+    fn splits_treasury_cut_between_beneficiaries() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        set_beneficiaries(
+            &mut account,
+            key(1),
+            &[(key(1), 5_000), (key(20), 3_333), (key(21), 1_667)],
+        )
+        .expect("beneficiaries must be accepted");
+
+        contribute(&mut account, key(9), 1_001).expect("contribute must succeed");
+        close_round(&mut account).expect("close must succeed");
+
+        let operator = owner_withdraw(&mut account).expect("operator withdraw must succeed");
+        let creators = beneficiary_withdraw(&mut account, key(20)).expect("creators must succeed");
+        assert!(!account.current_round.owner_has_withdrawn);
+        let reserve = beneficiary_withdraw(&mut account, key(21)).expect("reserve must succeed");
+
+        assert_eq!(operator, 51);
+        assert_eq!(creators, 33);
+        assert_eq!(reserve, 16);
+        assert_eq!(operator + creators + reserve, 100);
+        assert_eq!(account.current_round.owner_withdrawable_lamports, 0);
+        assert!(account.current_round.owner_has_withdrawn);
+
+        let second = beneficiary_withdraw(&mut account, key(20));
+        assert_eq!(second, Err(ProgramError::DoubleWithdraw));
+    }
+
+    #[test]
+    fn rejects_invalid_beneficiary_shares() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+
+        assert_eq!(
+            set_beneficiaries(&mut account, key(2), &[(key(2), 10_000)]),
+            Err(ProgramError::UnauthorizedOwner)
+        );
+        assert_eq!(
+            set_beneficiaries(&mut account, key(1), &[(key(1), 5_000), (key(2), 4_000)]),
+            Err(ProgramError::InvalidBeneficiaryShares)
+        );
+        assert_eq!(
+            set_beneficiaries(&mut account, key(1), &[(key(1), 5_000), (key(1), 5_000)]),
+            Err(ProgramError::DuplicateBeneficiary)
+        );
+        assert_eq!(
+            set_beneficiaries(&mut account, key(1), &[(key(1), 10_000), (key(2), 0)]),
+            Err(ProgramError::InvalidBeneficiaryShares)
+        );
+        assert_eq!(
+            set_beneficiaries(&mut account, key(1), &[(key(2), 10_000)]),
+            Err(ProgramError::OwnerNotBeneficiary)
+        );
+        assert_eq!(
+            beneficiary_withdraw(&mut account, key(2)),
+            Err(ProgramError::RoundStillOpen)
+        );
+    }
+
+    #[test]
+    fn failed_contribute_leaves_account_untouched() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(3), u64::MAX).expect("contribute must succeed");
+        let snapshot = account.clone();
+
+        let result = contribute(&mut account, key(4), 1);
+        assert_eq!(result, Err(ProgramError::MathOverflow));
+        assert_eq!(account, snapshot);
+        assert!(find_contributor_slot(&account.contributors, key(4)).is_none());
+    }
+
+    #[test]
+    fn failed_close_round_leaves_account_untouched() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(3), 500).expect("contribute must succeed");
+        contribute(&mut account, key(4), 700).expect("contribute must succeed");
+        account.beneficiaries = vec![BeneficiaryRecord::empty(); MAX_BENEFICIARIES];
+        let snapshot = account.clone();
+
+        let result = close_round(&mut account);
+        assert_eq!(result, Err(ProgramError::BeneficiaryNotFound));
+        assert_eq!(account, snapshot);
+    }
+
+    #[test]
+    fn failed_claim_and_withdraw_leave_account_untouched() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(3), 1_000).expect("contribute must succeed");
+        close_round(&mut account).expect("close must succeed");
+        account.current_round.total_round_claimed = u64::MAX;
+        account.current_round.owner_withdrawable_lamports = 0;
+        let snapshot = account.clone();
+
+        assert_eq!(claim(&mut account, key(3)), Err(ProgramError::MathOverflow));
+        assert_eq!(
+            owner_withdraw(&mut account),
+            Err(ProgramError::MathOverflow)
+        );
+        assert_eq!(account, snapshot);
+    }
+
+    #[test]
+    fn contribute_batch_applies_every_entry_and_reports_totals() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(2), 100).expect("contribute must succeed");
+
+        let totals = contribute_batch(&mut account, &[(key(2), 50), (key(3), 200), (key(2), 25)])
+            .expect("batch must succeed");
+
+        assert_eq!(totals, vec![150, 200, 175]);
+        assert_eq!(account.current_round.total_round_contributions, 375);
+    }
+
+    #[test]
+    fn failed_batches_leave_account_untouched() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(2), 1_000).expect("contribute must succeed");
+        let snapshot = account.clone();
+
+        let result = contribute_batch(&mut account, &[(key(3), 10), (key(4), 0)]);
+        assert_eq!(
+            result,
+            Err(ProgramError::BatchEntryFailed {
+                index: 1,
+                error: Box::new(ProgramError::InvalidAmount),
+            })
+        );
+        assert_eq!(account, snapshot);
+
+        close_round(&mut account).expect("close must succeed");
+        let snapshot = account.clone();
+
+        let result = claim_batch(&mut account, &[key(2), key(2)]);
+        assert_eq!(
+            result,
+            Err(ProgramError::BatchEntryFailed {
+                index: 1,
+                error: Box::new(ProgramError::DoubleClaim),
+            })
+        );
+        assert_eq!(account, snapshot);
+
+        let receipts = claim_batch(&mut account, &[key(2)]).expect("claim batch must succeed");
+        assert_eq!(
+            receipts,
+            vec![ClaimReceipt {
+                contributor: key(2),
+                recipient: key(2),
+                lamports: 900,
+            }]
+        );
+        assert_eq!(account.current_round.total_round_claimed, 900);
+    }
+
+    #[test]
+    fn preview_close_matches_close_round_without_mutating() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(2), 1_005).expect("contribute must succeed");
+        contribute(&mut account, key(3), 2_007).expect("contribute must succeed");
+        let snapshot = account.clone();
+
+        let preview = preview_close(&account).expect("preview must succeed");
+        assert_eq!(account, snapshot);
+
+        assert_eq!(preview.total_round_contributions, 3_012);
+        assert_eq!(preview.treasury_cut_lamports, 301);
+        assert_eq!(preview.total_round_claimable, 2_711);
+        assert_eq!(preview.contributors.len(), 2);
+        assert_eq!(preview.contributors[0].claimable_lamports, 904);
+        assert_eq!(preview.contributors[1].claimable_lamports, 1_806);
+        assert_eq!(preview.dust_lamports, 1);
+        assert_eq!(preview.beneficiaries[0].withdrawable_lamports, 301);
+
+        close_round(&mut account).expect("close must succeed");
+        assert_eq!(claim(&mut account, key(2)), Ok(904));
+        assert_eq!(claim(&mut account, key(3)), Ok(1_806));
+        assert_eq!(owner_withdraw(&mut account), Ok(301));
+        assert_eq!(
+            preview_close(&account),
+            Err(ProgramError::RoundAlreadyClosed)
+        );
+    }
+
+    #[test]
+    fn delegate_claims_to_registered_payout_address_across_rounds() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(2), 1_000).expect("contribute must succeed");
+        assert_eq!(
+            set_payout_address(&mut account, key(31), key(2), key(31)),
+            Err(ProgramError::UnauthorizedCaller)
+        );
+        assert_eq!(
+            set_claim_delegate(&mut account, key(31), key(2), key(31)),
+            Err(ProgramError::UnauthorizedCaller)
+        );
+        set_payout_address(&mut account, key(2), key(2), key(30))
+            .expect("payout address must be set");
+        set_claim_delegate(&mut account, key(2), key(2), key(31)).expect("delegate must be set");
+        close_round(&mut account).expect("close must succeed");
+
+        assert_eq!(
+            claim_for(&mut account, key(32), key(2)),
+            Err(ProgramError::UnauthorizedClaimant)
+        );
+
+        let receipt =
+            claim_for(&mut account, key(31), key(2)).expect("delegate claim must succeed");
+        assert_eq!(
+            receipt,
+            ClaimReceipt {
+                contributor: key(2),
+                recipient: key(30),
+                lamports: 900,
+            }
+        );
+
+        reset_round(&mut account).expect("reset must succeed");
+        contribute(&mut account, key(2), 2_000).expect("contribute must succeed");
+        close_round(&mut account).expect("close must succeed");
+
+        let receipt =
+            claim_for(&mut account, key(31), key(2)).expect("delegate claim must succeed");
+        assert_eq!(receipt.recipient, key(30));
+        assert_eq!(receipt.lamports, 1_800);
+
+        set_payout_address(&mut account, key(2), key(2), [0u8; 32])
+            .expect("payout address must clear");
+        assert_eq!(payout_address(&account, key(2)), Ok(key(2)));
+    }
+
+    #[test]
+    fn relayer_batch_claims_pay_registered_addresses() {
+        let mut account = initialize(key(1), 1_000).expect("init must succeed");
+        contribute(&mut account, key(2), 1_000).expect("contribute must succeed");
+        contribute(&mut account, key(3), 2_000).expect("contribute must succeed");
+        set_payout_address(&mut account, key(2), key(2), key(40))
+            .expect("payout address must be set");
+        close_round(&mut account).expect("close must succeed");
+
+        let receipts =
+            claim_batch(&mut account, &[key(2), key(3)]).expect("claim batch must succeed");
+        assert_eq!(receipts[0].recipient, key(40));
+        assert_eq!(receipts[0].lamports, 900);
+        assert_eq!(receipts[1].recipient, key(3));
+        assert_eq!(receipts[1].lamports, 1_800);
+    }
 }
@@ -1,141 +1,700 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::solana_program::sysvar::instructions::get_instruction_relative;
+use anchor_lang::solana_program::{ed25519_program, sysvar};
+use consumer::{encode_roll_callback, RollCallback};
+use sampling::{RandomnessCursor, SampleWidth, SamplingError};
 
 declare_id!("7fB9iz3f9t3CFjYg8G9Y1vWmoW8hS1E7xJ4WwGmXh7Xu");
 
+pub const MAX_ORACLES: usize = 5;
+pub const ROLL_HISTORY_LEN: usize = 8;
+pub const DICE_OUTCOMES: usize = 11;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_PAYOUT_BPS: u32 = 1_000_000;
+
+pub const GAME_SEED: &[u8] = b"game";
+pub const RANDOMNESS_SEED: &[u8] = b"randomness";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const ADMIN_SEED: &[u8] = b"admin";
+pub const PLAYER_SEED: &[u8] = b"player";
+
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_SIZE: usize = 14;
+const ED25519_PUBKEY_SIZE: usize = 32;
+const ED25519_SIGNATURE_SIZE: usize = 64;
+
 #[program]
 pub mod word_game_anchor {
     use super::*;
 
     pub fn initialize_game(
         ctx: Context<InitializeGame>,
-        request_cooldown_seconds: i64,
-        max_retries: u8,
+        game_id: u64,
+        params: InitializeGameParams,
     ) -> Result<()> {
+        let InitializeGameParams {
+            request_cooldown,
+            cooldown_unit,
+            max_retries,
+            request_expiry_slots,
+            payout_table_bps,
+            bankroll_lamports,
+        } = params;
+        require!(request_cooldown >= 0, WordGameError::InvalidCooldown);
+        require!(max_retries > 0, WordGameError::InvalidRetryLimit);
         require!(
-            request_cooldown_seconds >= 0,
-            WordGameError::InvalidCooldown
+            request_expiry_slots > 0,
+            WordGameError::InvalidRequestExpiry
+        );
+        require!(
+            payout_table_bps.iter().all(|bps| *bps <= MAX_PAYOUT_BPS),
+            WordGameError::InvalidPayoutTable
         );
-        require!(max_retries > 0, WordGameError::InvalidRetryLimit);
 
         let game = &mut ctx.accounts.game;
         let randomness = &mut ctx.accounts.randomness;
 
+        game.bump = ctx.bumps.game;
+        game.game_id = game_id;
         game.authority = ctx.accounts.authority.key();
         game.randomness_account = randomness.key();
         game.status = GameStatus::ReadyToRoll;
         game.settled = false;
         game.last_request_timestamp = 0;
-        game.request_cooldown_seconds = request_cooldown_seconds;
+        game.request_cooldown = request_cooldown;
+        game.cooldown_unit = cooldown_unit;
+        game.last_request_slot = 0;
+        game.paused = false;
+        game.pause_ledger = PauseLedger::default();
+        game.request_paused_snapshot = 0;
+        game.consumer_program = Pubkey::default();
+        game.pending_callback = None;
+        game.player_liabilities = 0;
+        game.pending_player_requests = 0;
+        game.player_cooldown = 0;
+        game.player_request_window = 0;
+        game.max_player_requests_per_window = 0;
         game.retry_count = 0;
         game.max_retries = max_retries;
         game.last_callback_slot = 0;
         game.pending_request_id = [0u8; 32];
         game.last_outcome = 0;
+        game.last_die_faces = [0u8; 2];
+        game.point = 0;
+        game.result = GameResult::Pending;
+        game.archived_outcome = 0;
+        game.archived_result = GameResult::Pending;
+        game.completed_games = 0;
+        game.request_expiry_slots = request_expiry_slots;
+        game.pending_request_slot = 0;
+        game.request_nonce = 0;
+        game.payout_table_bps = payout_table_bps;
+        game.pending_stake = 0;
+        game.claimable_lamports = 0;
+
+        let vault = &mut ctx.accounts.vault;
+        vault.game = game.key();
+        vault.bump = ctx.bumps.vault;
 
+        randomness.bump = ctx.bumps.randomness;
         randomness.authority = ctx.accounts.authority.key();
         randomness.game = game.key();
-        randomness.oracle_authority = ctx.accounts.oracle_authority.key();
         randomness.last_request_id = [0u8; 32];
         randomness.last_fulfilled_request_id = [0u8; 32];
         randomness.last_callback_slot = 0;
+        randomness.mode = RandomnessMode::Oracle;
+        randomness.commitment = [0u8; 32];
+        configure_oracle_set(randomness, &[ctx.accounts.oracle_authority.key()], 1)?;
 
+        transfer_lamports(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            bankroll_lamports,
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn request_roll(ctx: Context<RequestRoll>, stake_lamports: u64) -> Result<()> {
+        let accounts = ctx.accounts;
+        let authority_key = accounts.authority.key();
+        open_staked_roll(
+            StakedRollAccounts {
+                game: &mut accounts.game,
+                randomness: &mut accounts.randomness,
+                vault: &accounts.vault,
+                admin_config: &accounts.admin_config,
+                player_state: None,
+                payer: accounts.authority.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            },
+            authority_key,
+            RandomnessMode::Oracle,
+            stake_lamports,
+        )?;
         Ok(())
     }
 
-    pub fn request_roll(ctx: Context<RequestRoll>, request_id: [u8; 32]) -> Result<()> {
+    pub fn request_roll_cpi(ctx: Context<RequestRollCpi>, stake_lamports: u64) -> Result<()> {
+        let accounts = ctx.accounts;
+        let authority_key = accounts.authority.key();
+        open_staked_roll(
+            StakedRollAccounts {
+                game: &mut accounts.game,
+                randomness: &mut accounts.randomness,
+                vault: &accounts.vault,
+                admin_config: &accounts.admin_config,
+                player_state: None,
+                payer: accounts.payer.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            },
+            authority_key,
+            RandomnessMode::Oracle,
+            stake_lamports,
+        )?;
+        Ok(())
+    }
+
+    pub fn consume_randomness_callback(
+        ctx: Context<ConsumeRandomnessCallback>,
+        request_id: [u8; 32],
+        randomness_bytes: Vec<u8>,
+    ) -> Result<()> {
         let game_key = ctx.accounts.game.key();
         let randomness_key = ctx.accounts.randomness.key();
-        let authority_key = ctx.accounts.authority.key();
 
         let game = &mut ctx.accounts.game;
         let randomness = &mut ctx.accounts.randomness;
 
-        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
-
+        validate_randomness_link(game, game_key, randomness, randomness_key, game.authority)?;
+        ensure_not_paused(game, &ctx.accounts.admin_config)?;
         require!(
-            matches!(
-                game.status,
-                GameStatus::ReadyToRoll | GameStatus::PointEstablished
-            ),
-            WordGameError::InvalidGameState
+            randomness.mode == RandomnessMode::Oracle,
+            WordGameError::RandomnessModeMismatch
         );
-        require!(!game.settled, WordGameError::GameAlreadySettled);
+        let oracle_key = ctx.accounts.oracle_authority.key();
+        let oracle_index = randomness
+            .oracle_index(&oracle_key)
+            .ok_or(WordGameError::InvalidOracleAuthority)?;
+        require!(request_id != [0u8; 32], WordGameError::InvalidRequestId);
 
-        let now = Clock::get()?.unix_timestamp;
-        if game.last_request_timestamp != 0 {
-            let elapsed = now.saturating_sub(game.last_request_timestamp);
-            require!(
-                elapsed >= game.request_cooldown_seconds,
-                WordGameError::CooldownActive
+        let signature_ix = get_instruction_relative(-1, &ctx.accounts.instructions_sysvar)
+            .map_err(|_| error!(WordGameError::MissingOracleSignature))?;
+        verify_oracle_signature_instruction(
+            &signature_ix,
+            &oracle_key,
+            &oracle_fulfillment_message(&game_key, &request_id, &randomness_bytes),
+        )?;
+
+        let slot = Clock::get()?.slot;
+        let threshold = randomness.oracle_threshold;
+
+        if let Some(player_state) = ctx.accounts.player_state.as_mut() {
+            let paused_slots = paused_slots_since(
+                game,
+                &ctx.accounts.admin_config,
+                player_state.request_paused_snapshot,
+                slot,
             );
+            validate_player_fulfillment(game, player_state, request_id, slot, paused_slots)?;
+            let Some(payload) = record_oracle_contribution(
+                &mut player_state.oracle_round,
+                threshold,
+                oracle_index,
+                request_id,
+                &randomness_bytes,
+            )?
+            else {
+                return Ok(());
+            };
+            return fulfill_player_roll(game, randomness, player_state, request_id, slot, &payload);
         }
 
+        require!(!game.settled, WordGameError::GameAlreadySettled);
+        let paused_slots = paused_slots_since(
+            game,
+            &ctx.accounts.admin_config,
+            game.request_paused_snapshot,
+            slot,
+        );
+        validate_fulfillment(game, randomness, request_id, slot, paused_slots)?;
+
+        let Some(payload) = record_oracle_contribution(
+            &mut randomness.oracle_round,
+            threshold,
+            oracle_index,
+            request_id,
+            &randomness_bytes,
+        )?
+        else {
+            return Ok(());
+        };
+
+        fulfill_roll(game, randomness, request_id, slot, &payload)
+    }
+
+    pub fn commit_oracle_randomness(
+        ctx: Context<CommitOracleRandomness>,
+        request_id: [u8; 32],
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+
+        let game = &ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, game.authority)?;
+        ensure_not_paused(game, &ctx.accounts.admin_config)?;
         require!(
-            game.retry_count < game.max_retries,
-            WordGameError::RetriesExhausted
+            randomness.mode == RandomnessMode::Oracle,
+            WordGameError::RandomnessModeMismatch
         );
+        let oracle_index = randomness
+            .oracle_index(&ctx.accounts.oracle_authority.key())
+            .ok_or(WordGameError::InvalidOracleAuthority)?;
         require!(request_id != [0u8; 32], WordGameError::InvalidRequestId);
 
-        game.last_request_timestamp = now;
-        game.retry_count = game.retry_count.saturating_add(1);
-        game.pending_request_id = request_id;
-        randomness.last_request_id = request_id;
+        let slot = Clock::get()?.slot;
+        let threshold = randomness.oracle_threshold;
+
+        if let Some(player_state) = ctx.accounts.player_state.as_mut() {
+            let paused_slots = paused_slots_since(
+                game,
+                &ctx.accounts.admin_config,
+                player_state.request_paused_snapshot,
+                slot,
+            );
+            validate_player_fulfillment(game, player_state, request_id, slot, paused_slots)?;
+            return record_oracle_commitment(
+                &mut player_state.oracle_round,
+                threshold,
+                oracle_index,
+                commitment,
+            );
+        }
+
+        require!(!game.settled, WordGameError::GameAlreadySettled);
+        let paused_slots = paused_slots_since(
+            game,
+            &ctx.accounts.admin_config,
+            game.request_paused_snapshot,
+            slot,
+        );
+        validate_fulfillment(game, randomness, request_id, slot, paused_slots)?;
+
+        record_oracle_commitment(
+            &mut randomness.oracle_round,
+            threshold,
+            oracle_index,
+            commitment,
+        )
+    }
+
+    pub fn commit_roll(
+        ctx: Context<RequestRoll>,
+        commitment: [u8; 32],
+        stake_lamports: u64,
+    ) -> Result<()> {
+        require!(commitment != [0u8; 32], WordGameError::InvalidCommitment);
 
+        let accounts = ctx.accounts;
+        let authority_key = accounts.authority.key();
+        open_staked_roll(
+            StakedRollAccounts {
+                game: &mut accounts.game,
+                randomness: &mut accounts.randomness,
+                vault: &accounts.vault,
+                admin_config: &accounts.admin_config,
+                player_state: None,
+                payer: accounts.authority.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            },
+            authority_key,
+            RandomnessMode::CommitReveal,
+            stake_lamports,
+        )?;
+        accounts.randomness.commitment = commitment;
         Ok(())
     }
 
-    pub fn consume_randomness_callback(
-        ctx: Context<ConsumeRandomnessCallback>,
+    pub fn reveal_roll(
+        ctx: Context<RevealRoll>,
         request_id: [u8; 32],
-        randomness_bytes: Vec<u8>,
+        secret: [u8; 32],
     ) -> Result<()> {
         let game_key = ctx.accounts.game.key();
         let randomness_key = ctx.accounts.randomness.key();
+        let authority_key = ctx.accounts.authority.key();
 
         let game = &mut ctx.accounts.game;
         let randomness = &mut ctx.accounts.randomness;
 
-        validate_randomness_link(game, game_key, randomness, randomness_key, game.authority)?;
-        require_keys_eq!(
-            ctx.accounts.oracle_authority.key(),
-            randomness.oracle_authority,
-            WordGameError::InvalidOracleAuthority
+        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
+        ensure_not_paused(game, &ctx.accounts.admin_config)?;
+        require!(
+            randomness.mode == RandomnessMode::CommitReveal,
+            WordGameError::RandomnessModeMismatch
         );
         require!(!game.settled, WordGameError::GameAlreadySettled);
         require!(request_id != [0u8; 32], WordGameError::InvalidRequestId);
 
         let slot = Clock::get()?.slot;
-        require!(
-            slot > game.last_callback_slot,
-            WordGameError::NonMonotonicCallbackSlot
+        let paused_slots = paused_slots_since(
+            game,
+            &ctx.accounts.admin_config,
+            game.request_paused_snapshot,
+            slot,
         );
+        validate_fulfillment(game, randomness, request_id, slot, paused_slots)?;
+
+        let slot_hashes = ctx.accounts.recent_slot_hashes.try_borrow_data()?;
+        let payload = reveal_payload(
+            randomness,
+            request_id,
+            &secret,
+            game.pending_request_slot,
+            &slot_hashes,
+        )?;
+        drop(slot_hashes);
+
+        fulfill_roll(game, randomness, request_id, slot, &payload)
+    }
+
+    pub fn forfeit_unrevealed_roll(ctx: Context<ForfeitUnrevealedRoll>) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+
+        let game = &mut ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, game.authority)?;
+        ensure_not_paused(game, &ctx.accounts.admin_config)?;
         require!(
-            slot > randomness.last_callback_slot,
-            WordGameError::NonMonotonicCallbackSlot
+            randomness.mode == RandomnessMode::CommitReveal,
+            WordGameError::RandomnessModeMismatch
+        );
+
+        let slot = Clock::get()?.slot;
+        let paused_slots = paused_slots_since(
+            game,
+            &ctx.accounts.admin_config,
+            game.request_paused_snapshot,
+            slot,
         );
+        cancel_expired_request(game, randomness, slot, paused_slots)
+    }
+
+    pub fn set_randomness_mode(
+        ctx: Context<SetRandomnessMode>,
+        mode: RandomnessMode,
+    ) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+        let authority_key = ctx.accounts.authority.key();
+
+        let game = &ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
 
+        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
         require!(
-            game.pending_request_id == request_id,
-            WordGameError::RequestCorrelationMismatch
+            game.pending_request_id == [0u8; 32] && game.pending_player_requests == 0,
+            WordGameError::RequestPending
         );
+
+        randomness.mode = mode;
+        randomness.commitment = [0u8; 32];
+        Ok(())
+    }
+
+    pub fn start_new_game(ctx: Context<StartNewGame>) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+        let authority_key = ctx.accounts.authority.key();
+
+        let game = &mut ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
+        require!(game.settled, WordGameError::GameNotSettled);
+
+        reset_for_new_game(game, randomness)
+    }
+
+    pub fn close_game(ctx: Context<CloseGame>) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+        let authority_key = ctx.accounts.authority.key();
+
+        let game = &ctx.accounts.game;
+        let randomness = &ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
+        ensure_closable(game)
+    }
+
+    pub fn initialize_admin(ctx: Context<InitializeAdmin>, guardian: Pubkey) -> Result<()> {
+        let admin_config = &mut ctx.accounts.admin_config;
+        admin_config.bump = ctx.bumps.admin_config;
+        admin_config.admin = ctx.accounts.admin.key();
+        admin_config.guardian = guardian;
+        admin_config.paused = false;
+        admin_config.pause_ledger = PauseLedger::default();
+        Ok(())
+    }
+
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        ctx.accounts.admin_config.guardian = guardian;
+        Ok(())
+    }
+
+    pub fn set_global_pause(ctx: Context<SetGlobalPause>, paused: bool) -> Result<()> {
+        let admin_config = &mut ctx.accounts.admin_config;
+        authorize_pause_change(admin_config, ctx.accounts.signer.key(), paused)?;
+        let was_paused = admin_config.paused;
+        admin_config
+            .pause_ledger
+            .record_change(was_paused, paused, Clock::get()?.slot);
+        admin_config.paused = paused;
+        Ok(())
+    }
+
+    pub fn set_game_paused(ctx: Context<SetGamePaused>, paused: bool) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let was_paused = game.paused;
+        game.pause_ledger
+            .record_change(was_paused, paused, Clock::get()?.slot);
+        game.paused = paused;
+        Ok(())
+    }
+
+    pub fn set_consumer_program(
+        ctx: Context<SetConsumerProgram>,
+        consumer_program: Pubkey,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        game.consumer_program = consumer_program;
+        game.pending_callback = None;
+        Ok(())
+    }
+
+    pub fn deliver_roll_callback<'info>(
+        ctx: Context<'_, '_, '_, 'info, DeliverRollCallback<'info>>,
+    ) -> Result<()> {
+        let callback = take_pending_callback(&mut ctx.accounts.game)?;
+        notify_consumer(
+            &ctx.accounts.game,
+            &ctx.accounts.consumer_program,
+            ctx.remaining_accounts,
+            &callback,
+        )
+    }
+
+    pub fn set_player_limits(
+        ctx: Context<SetPlayerLimits>,
+        player_cooldown: i64,
+        player_request_window: i64,
+        max_player_requests_per_window: u32,
+    ) -> Result<()> {
+        require!(player_cooldown >= 0, WordGameError::InvalidCooldown);
         require!(
-            randomness.last_request_id == request_id,
-            WordGameError::RequestCorrelationMismatch
+            max_player_requests_per_window == 0 || player_request_window > 0,
+            WordGameError::InvalidRateLimit
         );
 
-        let outcome = rejection_sample_sum_2_to_12(&randomness_bytes)?;
-        game.last_outcome = outcome;
-        game.last_callback_slot = slot;
-        game.pending_request_id = [0u8; 32];
-        game.retry_count = 0;
-        game.settled = true;
-        game.status = GameStatus::Settled;
+        let game = &mut ctx.accounts.game;
+        game.player_cooldown = player_cooldown;
+        game.player_request_window = player_request_window;
+        game.max_player_requests_per_window = max_player_requests_per_window;
+        Ok(())
+    }
+
+    pub fn register_player(ctx: Context<RegisterPlayer>) -> Result<()> {
+        let player_state = &mut ctx.accounts.player_state;
+        player_state.bump = ctx.bumps.player_state;
+        player_state.game = ctx.accounts.game.key();
+        player_state.player = ctx.accounts.player.key();
+        player_state.last_request_at = 0;
+        player_state.window_start = 0;
+        player_state.window_request_count = 0;
+        player_state.total_requests = 0;
+        player_state.pending_request_id = [0u8; 32];
+        player_state.pending_request_slot = 0;
+        player_state.request_paused_snapshot = 0;
+        player_state.pending_stake = 0;
+        player_state.reserved_payout = 0;
+        player_state.oracle_round = OracleRound::default();
+        player_state.last_die_faces = [0u8; 2];
+        player_state.last_outcome = 0;
+        player_state.last_payout_lamports = 0;
+        player_state.claimable_lamports = 0;
+        Ok(())
+    }
+
+    pub fn request_roll_as_player(
+        ctx: Context<RequestRollAsPlayer>,
+        stake_lamports: u64,
+    ) -> Result<()> {
+        let accounts = ctx.accounts;
+        let authority_key = accounts.game.authority;
+        open_staked_roll(
+            StakedRollAccounts {
+                game: &mut accounts.game,
+                randomness: &mut accounts.randomness,
+                vault: &accounts.vault,
+                admin_config: &accounts.admin_config,
+                player_state: Some(&mut accounts.player_state),
+                payer: accounts.player.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+            },
+            authority_key,
+            RandomnessMode::Oracle,
+            stake_lamports,
+        )?;
+        Ok(())
+    }
+
+    pub fn cancel_player_request(ctx: Context<CancelPlayerRequest>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let paused_slots = paused_slots_since(
+            &ctx.accounts.game,
+            &ctx.accounts.admin_config,
+            ctx.accounts.player_state.request_paused_snapshot,
+            slot,
+        );
+        cancel_expired_player_request(
+            &mut ctx.accounts.game,
+            &mut ctx.accounts.player_state,
+            slot,
+            paused_slots,
+        )
+    }
+
+    pub fn claim_player_winnings(ctx: Context<ClaimPlayerWinnings>) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let rent_minimum = Rent::get()?.minimum_balance(GameVault::SPACE);
+        let amount = take_player_claimable(
+            &mut ctx.accounts.game,
+            &mut ctx.accounts.player_state,
+            vault_info.lamports(),
+            rent_minimum,
+        )?;
+        pay_from_vault(&vault_info, &ctx.accounts.player.to_account_info(), amount)
+    }
+
+    pub fn close_player(ctx: Context<ClosePlayer>) -> Result<()> {
+        ensure_player_closable(&ctx.accounts.player_state)
+    }
+
+    pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
+        let vault_info = ctx.accounts.vault.to_account_info();
+        let rent_minimum = Rent::get()?.minimum_balance(GameVault::SPACE);
+        let amount = take_claimable(&mut ctx.accounts.game, vault_info.lamports(), rent_minimum)?;
+        pay_from_vault(
+            &vault_info,
+            &ctx.accounts.authority.to_account_info(),
+            amount,
+        )
+    }
+
+    pub fn cancel_pending_request(ctx: Context<CancelPendingRequest>) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+        let authority_key = ctx.accounts.authority.key();
+
+        let game = &mut ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
+
+        let slot = Clock::get()?.slot;
+        let paused_slots = paused_slots_since(
+            game,
+            &ctx.accounts.admin_config,
+            game.request_paused_snapshot,
+            slot,
+        );
+        cancel_expired_request(game, randomness, slot, paused_slots)
+    }
+
+    pub fn propose_oracle_authority(
+        ctx: Context<ProposeOracleAuthority>,
+        current_oracle_authority: Pubkey,
+        new_oracle_authority: Pubkey,
+    ) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+        let authority_key = ctx.accounts.authority.key();
 
-        randomness.last_fulfilled_request_id = request_id;
-        randomness.last_callback_slot = slot;
+        let game = &ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
+        require!(
+            randomness.oracle_index(&current_oracle_authority).is_some(),
+            WordGameError::InvalidOracleAuthority
+        );
+        require!(
+            new_oracle_authority != Pubkey::default()
+                && randomness.oracle_index(&new_oracle_authority).is_none(),
+            WordGameError::InvalidOracleAuthority
+        );
 
+        randomness.replaced_oracle_authority = current_oracle_authority;
+        randomness.pending_oracle_authority = new_oracle_authority;
         Ok(())
     }
+
+    pub fn accept_oracle_authority(ctx: Context<AcceptOracleAuthority>) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+        let new_oracle_key = ctx.accounts.new_oracle_authority.key();
+
+        let game = &mut ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, game.authority)?;
+        rotate_oracle_authority(game, randomness, new_oracle_key)
+    }
+
+    pub fn set_oracle_set(
+        ctx: Context<SetOracleSet>,
+        oracle_authorities: Vec<Pubkey>,
+        oracle_threshold: u8,
+    ) -> Result<()> {
+        let game_key = ctx.accounts.game.key();
+        let randomness_key = ctx.accounts.randomness.key();
+        let authority_key = ctx.accounts.authority.key();
+
+        let game = &ctx.accounts.game;
+        let randomness = &mut ctx.accounts.randomness;
+
+        validate_randomness_link(game, game_key, randomness, randomness_key, authority_key)?;
+        // Player rounds record commitments by oracle index, so the set stays
+        // fixed until every player request has settled or expired.
+        require!(
+            game.pending_request_id == [0u8; 32] && game.pending_player_requests == 0,
+            WordGameError::RequestPending
+        );
+
+        configure_oracle_set(randomness, &oracle_authorities, oracle_threshold)
+    }
+}
+
+pub fn find_game_address(authority: &Pubkey, game_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[GAME_SEED, authority.as_ref(), &game_id.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+pub fn find_randomness_address(game: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RANDOMNESS_SEED, game.as_ref()], &crate::ID)
 }
 
 fn validate_randomness_link(
@@ -168,98 +727,2055 @@ fn validate_randomness_link(
     Ok(())
 }
 
-pub fn rejection_sample_sum_2_to_12(randomness_bytes: &[u8]) -> Result<u8> {
-    const BINS: u8 = 11;
-    const ACCEPTANCE_BOUND: u8 = 253;
+pub fn derive_request_id(
+    game: &Pubkey,
+    request_nonce: u64,
+    slot: u64,
+    authority: &Pubkey,
+) -> [u8; 32] {
+    hashv(&[
+        game.as_ref(),
+        &request_nonce.to_le_bytes(),
+        &slot.to_le_bytes(),
+        authority.as_ref(),
+    ])
+    .to_bytes()
+}
 
-    for &byte in randomness_bytes {
-        if byte < ACCEPTANCE_BOUND {
-            return Ok((byte % BINS) + 2);
+fn cooldown_elapsed(game: &GameState, now: i64, slot: u64) -> bool {
+    match game.cooldown_unit {
+        CooldownUnit::Seconds => {
+            game.last_request_timestamp == 0
+                || now.saturating_sub(game.last_request_timestamp) >= game.request_cooldown
+        }
+        CooldownUnit::Slots => {
+            game.last_request_slot == 0
+                || slot.saturating_sub(game.last_request_slot) >= game.request_cooldown as u64
         }
     }
-
-    err!(WordGameError::InsufficientRandomnessEntropy)
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum GameStatus {
-    ReadyToRoll,
-    PointEstablished,
-    Settled,
-}
+fn admit_player_request(
+    game: &GameState,
+    player_state: &mut PlayerState,
+    clock: &Clock,
+) -> Result<()> {
+    require!(
+        game.max_player_requests_per_window > 0,
+        WordGameError::PlayerRollsDisabled
+    );
 
-#[account]
-pub struct GameState {
-    pub authority: Pubkey,
-    pub randomness_account: Pubkey,
-    pub status: GameStatus,
-    pub settled: bool,
-    pub last_request_timestamp: i64,
-    pub request_cooldown_seconds: i64,
-    pub retry_count: u8,
-    pub max_retries: u8,
-    pub last_callback_slot: u64,
-    pub pending_request_id: [u8; 32],
-    pub last_outcome: u8,
-}
+    let now = match game.cooldown_unit {
+        CooldownUnit::Seconds => clock.unix_timestamp,
+        CooldownUnit::Slots => clock.slot as i64,
+    };
+    if player_state.total_requests > 0 {
+        require!(
+            now.saturating_sub(player_state.last_request_at) >= game.player_cooldown,
+            WordGameError::CooldownActive
+        );
+    }
 
-impl GameState {
-    pub const SPACE: usize = 8 + 32 + 32 + 1 + 1 + 8 + 8 + 1 + 1 + 8 + 32 + 1;
-}
+    let (window_start, window_request_count) =
+        if now.saturating_sub(player_state.window_start) >= game.player_request_window {
+            (now, 0)
+        } else {
+            (player_state.window_start, player_state.window_request_count)
+        };
+    require!(
+        window_request_count < game.max_player_requests_per_window,
+        WordGameError::PlayerRateLimited
+    );
+    let total_requests = player_state
+        .total_requests
+        .checked_add(1)
+        .ok_or(WordGameError::MathOverflow)?;
 
-#[account]
-pub struct GameRandomness {
-    pub authority: Pubkey,
-    pub game: Pubkey,
-    pub oracle_authority: Pubkey,
-    pub last_request_id: [u8; 32],
-    pub last_fulfilled_request_id: [u8; 32],
-    pub last_callback_slot: u64,
+    player_state.last_request_at = now;
+    player_state.window_start = window_start;
+    player_state.window_request_count = window_request_count + 1;
+    player_state.total_requests = total_requests;
+    Ok(())
 }
 
-impl GameRandomness {
-    pub const SPACE: usize = 8 + 32 + 32 + 32 + 32 + 32 + 8;
-}
+fn open_player_request(
+    game: &mut GameState,
+    game_key: Pubkey,
+    player_state: &mut PlayerState,
+    clock: &Clock,
+) -> Result<[u8; 32]> {
+    require!(
+        player_state.pending_request_id == [0u8; 32],
+        WordGameError::RequestPending
+    );
+
+    let request_id = derive_request_id(
+        &game_key,
+        game.request_nonce,
+        clock.slot,
+        &player_state.player,
+    );
+    let pending_player_requests = game
+        .pending_player_requests
+        .checked_add(1)
+        .ok_or(WordGameError::MathOverflow)?;
+    game.request_nonce = game
+        .request_nonce
+        .checked_add(1)
+        .ok_or(WordGameError::MathOverflow)?;
+
+    game.pending_player_requests = pending_player_requests;
+    player_state.pending_request_id = request_id;
+    player_state.pending_request_slot = clock.slot;
+    Ok(request_id)
+}
+
+fn validate_player_fulfillment(
+    game: &GameState,
+    player_state: &PlayerState,
+    request_id: [u8; 32],
+    slot: u64,
+    paused_slots: u64,
+) -> Result<()> {
+    require!(
+        player_state.pending_request_id == request_id,
+        WordGameError::RequestCorrelationMismatch
+    );
+    require!(
+        !is_player_request_expired(game, player_state, slot, paused_slots),
+        WordGameError::RequestExpired
+    );
+    Ok(())
+}
+
+// Player rolls are single-roll wagers paid from the payout table. They never
+// touch the game's own craps round, so each player settles independently, and
+// they never queue a consumer callback.
+fn fulfill_player_roll(
+    game: &mut GameState,
+    randomness: &mut GameRandomness,
+    player_state: &mut PlayerState,
+    request_id: [u8; 32],
+    slot: u64,
+    payload: &[u8],
+) -> Result<()> {
+    let DiceSample {
+        faces: die_faces,
+        rejected_bytes,
+    } = sample_dice(payload)?;
+    let outcome = die_faces[0] + die_faces[1];
+    let payout_lamports = wager_payout(game, player_state.pending_stake, outcome)?;
+    let player_liabilities = game
+        .player_liabilities
+        .checked_sub(player_state.reserved_payout)
+        .and_then(|liabilities| liabilities.checked_add(payout_lamports))
+        .ok_or(WordGameError::MathOverflow)?;
+    let claimable_lamports = player_state
+        .claimable_lamports
+        .checked_add(payout_lamports)
+        .ok_or(WordGameError::MathOverflow)?;
+    let pending_player_requests = game
+        .pending_player_requests
+        .checked_sub(1)
+        .ok_or(WordGameError::MathOverflow)?;
+    let request_slot = player_state.pending_request_slot;
+    let randomness_hash = hashv(&[payload]).to_bytes();
+
+    game.player_liabilities = player_liabilities;
+    game.pending_player_requests = pending_player_requests;
+    player_state.claimable_lamports = claimable_lamports;
+    player_state.last_die_faces = die_faces;
+    player_state.last_outcome = outcome;
+    player_state.last_payout_lamports = payout_lamports;
+    clear_player_request(player_state);
+
+    randomness.record_outcome(outcome, rejected_bytes);
+    randomness.record_roll(RollRecord {
+        request_id,
+        request_slot,
+        fulfilled_slot: slot,
+        randomness_hash,
+        outcome,
+    });
+
+    emit!(RandomnessFulfilled {
+        game: randomness.game,
+        request_id,
+        slot,
+        randomness_hash,
+        die_faces,
+        outcome,
+        retry_count: 0,
+        payout_lamports,
+    });
+    Ok(())
+}
+
+fn clear_player_request(player_state: &mut PlayerState) {
+    player_state.pending_request_id = [0u8; 32];
+    player_state.pending_request_slot = 0;
+    player_state.pending_stake = 0;
+    player_state.reserved_payout = 0;
+    player_state.oracle_round = OracleRound::default();
+}
+
+fn is_player_request_expired(
+    game: &GameState,
+    player_state: &PlayerState,
+    slot: u64,
+    paused_slots: u64,
+) -> bool {
+    slot > player_state
+        .pending_request_slot
+        .saturating_add(game.request_expiry_slots)
+        .saturating_add(paused_slots)
+}
+
+fn cancel_expired_player_request(
+    game: &mut GameState,
+    player_state: &mut PlayerState,
+    slot: u64,
+    paused_slots: u64,
+) -> Result<()> {
+    require!(
+        player_state.pending_request_id != [0u8; 32],
+        WordGameError::NoPendingRequest
+    );
+    require!(
+        is_player_request_expired(game, player_state, slot, paused_slots),
+        WordGameError::RequestNotExpired
+    );
+
+    let player_liabilities = game
+        .player_liabilities
+        .checked_sub(player_state.reserved_payout)
+        .and_then(|liabilities| liabilities.checked_add(player_state.pending_stake))
+        .ok_or(WordGameError::MathOverflow)?;
+    let claimable_lamports = player_state
+        .claimable_lamports
+        .checked_add(player_state.pending_stake)
+        .ok_or(WordGameError::MathOverflow)?;
+    let pending_player_requests = game
+        .pending_player_requests
+        .checked_sub(1)
+        .ok_or(WordGameError::MathOverflow)?;
+
+    game.player_liabilities = player_liabilities;
+    game.pending_player_requests = pending_player_requests;
+    player_state.claimable_lamports = claimable_lamports;
+    clear_player_request(player_state);
+    Ok(())
+}
+
+struct StakedRollAccounts<'a, 'info> {
+    game: &'a mut Account<'info, GameState>,
+    randomness: &'a mut Account<'info, GameRandomness>,
+    vault: &'a Account<'info, GameVault>,
+    admin_config: &'a AdminConfig,
+    player_state: Option<&'a mut Account<'info, PlayerState>>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+// Shared by every instruction that opens a staked roll so their checks,
+// vault transfer and event cannot drift apart.
+fn open_staked_roll(
+    accounts: StakedRollAccounts<'_, '_>,
+    authority: Pubkey,
+    mode: RandomnessMode,
+    stake_lamports: u64,
+) -> Result<[u8; 32]> {
+    let StakedRollAccounts {
+        game,
+        randomness,
+        vault,
+        admin_config,
+        player_state,
+        payer,
+        system_program,
+    } = accounts;
+    let game_key = game.key();
+    let randomness_key = randomness.key();
+    let bankroll = available_bankroll(
+        game,
+        vault.to_account_info().lamports(),
+        Rent::get()?.minimum_balance(GameVault::SPACE),
+    );
+
+    validate_randomness_link(game, game_key, randomness, randomness_key, authority)?;
+    ensure_not_paused(game, admin_config)?;
+    require!(
+        randomness.mode == mode,
+        WordGameError::RandomnessModeMismatch
+    );
+
+    let clock = Clock::get()?;
+    let request_nonce = game.request_nonce;
+    let (player, request_id, retry_count) = match player_state {
+        Some(player_state) => {
+            admit_player_request(game, player_state, &clock)?;
+            let request_id = open_player_request(game, game_key, player_state, &clock)?;
+            place_player_wager(game, player_state, stake_lamports, bankroll)?;
+            player_state.request_paused_snapshot =
+                paused_slots_total(game, admin_config, clock.slot);
+            (player_state.player, request_id, 0)
+        }
+        None => {
+            let request_id = open_roll_request(game, game_key, randomness, authority, &clock)?;
+            place_wager(game, stake_lamports, bankroll)?;
+            game.request_paused_snapshot = paused_slots_total(game, admin_config, clock.slot);
+            (authority, request_id, game.retry_count)
+        }
+    };
+
+    transfer_lamports(
+        &payer,
+        &vault.to_account_info(),
+        stake_lamports,
+        &system_program,
+    )?;
+
+    emit!(RollRequested {
+        game: game_key,
+        player,
+        request_id,
+        request_nonce,
+        slot: clock.slot,
+        retry_count,
+        stake_lamports,
+    });
+    Ok(request_id)
+}
+
+fn open_roll_request(
+    game: &mut GameState,
+    game_key: Pubkey,
+    randomness: &mut GameRandomness,
+    authority: Pubkey,
+    clock: &Clock,
+) -> Result<[u8; 32]> {
+    require!(
+        matches!(
+            game.status,
+            GameStatus::ReadyToRoll | GameStatus::PointEstablished
+        ),
+        WordGameError::InvalidGameState
+    );
+    require!(!game.settled, WordGameError::GameAlreadySettled);
+    require!(
+        game.pending_request_id == [0u8; 32],
+        WordGameError::RequestPending
+    );
+    // Only one callback is held for delivery; a new roll would overwrite it.
+    require!(
+        game.pending_callback.is_none(),
+        WordGameError::UndeliveredRollCallback
+    );
+
+    require!(
+        cooldown_elapsed(game, clock.unix_timestamp, clock.slot),
+        WordGameError::CooldownActive
+    );
+
+    require!(
+        game.retry_count < game.max_retries,
+        WordGameError::RetriesExhausted
+    );
+
+    let request_id = derive_request_id(&game_key, game.request_nonce, clock.slot, &authority);
+    game.request_nonce = game
+        .request_nonce
+        .checked_add(1)
+        .ok_or(WordGameError::MathOverflow)?;
+
+    game.last_request_timestamp = clock.unix_timestamp;
+    game.last_request_slot = clock.slot;
+    game.retry_count = game.retry_count.saturating_add(1);
+    game.pending_request_id = request_id;
+    game.pending_request_slot = clock.slot;
+    randomness.last_request_id = request_id;
+    clear_oracle_contributions(randomness);
+
+    Ok(request_id)
+}
+
+fn validate_fulfillment(
+    game: &GameState,
+    randomness: &GameRandomness,
+    request_id: [u8; 32],
+    slot: u64,
+    paused_slots: u64,
+) -> Result<()> {
+    require!(
+        slot > game.last_callback_slot,
+        WordGameError::NonMonotonicCallbackSlot
+    );
+    require!(
+        slot > randomness.last_callback_slot,
+        WordGameError::NonMonotonicCallbackSlot
+    );
+
+    require!(
+        game.pending_request_id == request_id,
+        WordGameError::RequestCorrelationMismatch
+    );
+    require!(
+        randomness.last_request_id == request_id,
+        WordGameError::RequestCorrelationMismatch
+    );
+    require!(
+        !is_request_expired(game, slot, paused_slots),
+        WordGameError::RequestExpired
+    );
+    Ok(())
+}
+
+fn fulfill_roll(
+    game: &mut GameState,
+    randomness: &mut GameRandomness,
+    request_id: [u8; 32],
+    slot: u64,
+    payload: &[u8],
+) -> Result<()> {
+    let DiceSample {
+        faces: die_faces,
+        rejected_bytes,
+    } = sample_dice(payload)?;
+    let outcome = die_faces[0] + die_faces[1];
+    let payout_lamports = wager_payout(game, game.pending_stake, outcome)?;
+    let claimable_lamports = game
+        .claimable_lamports
+        .checked_add(payout_lamports)
+        .ok_or(WordGameError::MathOverflow)?;
+    let retry_count = game.retry_count;
+    let request_slot = game.pending_request_slot;
+    let randomness_hash = hashv(&[payload]).to_bytes();
+    game.last_die_faces = die_faces;
+    game.last_outcome = outcome;
+    game.last_callback_slot = slot;
+    game.pending_request_id = [0u8; 32];
+    game.pending_request_slot = 0;
+    game.retry_count = 0;
+    game.pending_stake = 0;
+    game.claimable_lamports = claimable_lamports;
+    apply_roll_outcome(game, outcome);
+
+    randomness.last_fulfilled_request_id = request_id;
+    randomness.last_callback_slot = slot;
+    randomness.commitment = [0u8; 32];
+    clear_oracle_contributions(randomness);
+    randomness.record_outcome(outcome, rejected_bytes);
+    randomness.record_roll(RollRecord {
+        request_id,
+        request_slot,
+        fulfilled_slot: slot,
+        randomness_hash,
+        outcome,
+    });
+
+    emit!(RandomnessFulfilled {
+        game: randomness.game,
+        request_id,
+        slot,
+        randomness_hash,
+        die_faces,
+        outcome,
+        retry_count,
+        payout_lamports,
+    });
+    if game.settled {
+        emit_game_settled(game, randomness.game, request_id, slot, retry_count);
+    }
+    if game.consumer_program != Pubkey::default() {
+        game.pending_callback = Some(roll_callback(game, randomness.game, request_id, slot));
+    }
+
+    Ok(())
+}
+
+// Pause policy: while a game or the whole program is paused, no roll can be
+// requested, committed, fulfilled, revealed or forfeited. A pause never refunds
+// or voids a request; the slots it spent paused are added to its expiry so it
+// can still be fulfilled, revealed or forfeited once play resumes.
+fn paused_slots_total(game: &GameState, admin_config: &AdminConfig, slot: u64) -> u64 {
+    game.pause_ledger
+        .paused_slots(game.paused, slot)
+        .saturating_add(
+            admin_config
+                .pause_ledger
+                .paused_slots(admin_config.paused, slot),
+        )
+}
+
+fn paused_slots_since(
+    game: &GameState,
+    admin_config: &AdminConfig,
+    snapshot: u64,
+    slot: u64,
+) -> u64 {
+    paused_slots_total(game, admin_config, slot).saturating_sub(snapshot)
+}
+
+fn ensure_not_paused(game: &GameState, admin_config: &AdminConfig) -> Result<()> {
+    require!(!admin_config.paused, WordGameError::GloballyPaused);
+    require!(!game.paused, WordGameError::GamePaused);
+    Ok(())
+}
+
+fn authorize_pause_change(admin_config: &AdminConfig, signer: Pubkey, paused: bool) -> Result<()> {
+    let is_admin = signer == admin_config.admin;
+    let is_guardian = signer == admin_config.guardian && paused;
+    require!(is_admin || is_guardian, WordGameError::InvalidAuthority);
+    Ok(())
+}
+
+fn available_bankroll(game: &GameState, vault_lamports: u64, rent_minimum: u64) -> u64 {
+    vault_lamports
+        .saturating_sub(rent_minimum)
+        .saturating_sub(game.claimable_lamports)
+        .saturating_sub(game.player_liabilities)
+}
+
+fn place_wager(game: &mut GameState, stake_lamports: u64, bankroll: u64) -> Result<()> {
+    if stake_lamports == 0 {
+        return Ok(());
+    }
+    let max_payout = payout_for(stake_lamports, max_payout_bps(game))?;
+    let covered = bankroll
+        .checked_add(stake_lamports)
+        .ok_or(WordGameError::MathOverflow)?;
+    require!(max_payout <= covered, WordGameError::StakeExceedsBankroll);
+
+    game.pending_stake = stake_lamports;
+    Ok(())
+}
+
+// Reserves the best-case payout of a player roll up front, after the game's
+// own pending wager, so concurrent player rolls can never overdraw the vault.
+fn place_player_wager(
+    game: &mut GameState,
+    player_state: &mut PlayerState,
+    stake_lamports: u64,
+    bankroll: u64,
+) -> Result<()> {
+    if stake_lamports == 0 {
+        return Ok(());
+    }
+    let max_bps = max_payout_bps(game);
+    let game_exposure = payout_for(game.pending_stake, max_bps)?;
+    let max_payout = payout_for(stake_lamports, max_bps)?;
+    let covered = bankroll
+        .saturating_sub(game_exposure)
+        .checked_add(stake_lamports)
+        .ok_or(WordGameError::MathOverflow)?;
+    require!(max_payout <= covered, WordGameError::StakeExceedsBankroll);
+
+    game.player_liabilities = game
+        .player_liabilities
+        .checked_add(max_payout)
+        .ok_or(WordGameError::MathOverflow)?;
+    player_state.pending_stake = stake_lamports;
+    player_state.reserved_payout = max_payout;
+    Ok(())
+}
+
+fn max_payout_bps(game: &GameState) -> u32 {
+    game.payout_table_bps.iter().copied().max().unwrap_or(0)
+}
+
+fn wager_payout(game: &GameState, stake_lamports: u64, outcome: u8) -> Result<u64> {
+    require!(
+        (2..=12).contains(&outcome),
+        WordGameError::InvalidSampleRange
+    );
+    payout_for(
+        stake_lamports,
+        game.payout_table_bps[(outcome - 2) as usize],
+    )
+}
+
+fn payout_for(stake_lamports: u64, payout_bps: u32) -> Result<u64> {
+    let payout = (stake_lamports as u128)
+        .checked_mul(payout_bps as u128)
+        .ok_or(WordGameError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(payout).map_err(|_| error!(WordGameError::MathOverflow))
+}
+
+fn refund_pending_stake(game: &mut GameState) -> Result<()> {
+    game.claimable_lamports = game
+        .claimable_lamports
+        .checked_add(game.pending_stake)
+        .ok_or(WordGameError::MathOverflow)?;
+    game.pending_stake = 0;
+    Ok(())
+}
+
+fn take_claimable(game: &mut GameState, vault_lamports: u64, rent_minimum: u64) -> Result<u64> {
+    let amount = game.claimable_lamports;
+    require!(amount > 0, WordGameError::NothingToClaim);
+    require!(
+        vault_lamports
+            .saturating_sub(rent_minimum)
+            .saturating_sub(game.player_liabilities)
+            >= amount,
+        WordGameError::InsufficientBankroll
+    );
+
+    game.claimable_lamports = 0;
+    Ok(amount)
+}
+
+fn take_player_claimable(
+    game: &mut GameState,
+    player_state: &mut PlayerState,
+    vault_lamports: u64,
+    rent_minimum: u64,
+) -> Result<u64> {
+    let amount = player_state.claimable_lamports;
+    require!(amount > 0, WordGameError::NothingToClaim);
+    require!(
+        vault_lamports.saturating_sub(rent_minimum) >= amount,
+        WordGameError::InsufficientBankroll
+    );
+
+    game.player_liabilities = game
+        .player_liabilities
+        .checked_sub(amount)
+        .ok_or(WordGameError::MathOverflow)?;
+    player_state.claimable_lamports = 0;
+    Ok(amount)
+}
+
+fn pay_from_vault(vault: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **vault.try_borrow_mut_lamports()? = vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(WordGameError::MathOverflow)?;
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(WordGameError::MathOverflow)?;
+    Ok(())
+}
+
+fn transfer_lamports<'a>(
+    from: &AccountInfo<'a>,
+    to: &AccountInfo<'a>,
+    amount: u64,
+    system_program: &AccountInfo<'a>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    invoke(
+        &system_instruction::transfer(from.key, to.key, amount),
+        &[from.clone(), to.clone(), system_program.clone()],
+    )?;
+    Ok(())
+}
+
+fn roll_callback(
+    game: &GameState,
+    game_key: Pubkey,
+    request_id: [u8; 32],
+    slot: u64,
+) -> RollCallback {
+    RollCallback {
+        game: game_key,
+        request_id,
+        slot,
+        die_faces: game.last_die_faces,
+        outcome: game.last_outcome,
+        point: game.point,
+        result: game.result,
+        settled: game.settled,
+    }
+}
+
+// Consumers are notified in a separate instruction so a failing consumer can
+// only revert its own delivery, never the settlement that produced the roll.
+fn take_pending_callback(game: &mut GameState) -> Result<RollCallback> {
+    game.pending_callback
+        .take()
+        .ok_or(error!(WordGameError::NoPendingCallback))
+}
+
+fn notify_consumer<'info>(
+    game: &Account<'info, GameState>,
+    consumer_program: &UncheckedAccount<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    callback: &RollCallback,
+) -> Result<()> {
+    require!(
+        consumer_program.executable,
+        WordGameError::ConsumerProgramMismatch
+    );
+
+    let game_info = game.to_account_info();
+    let mut accounts = vec![AccountMeta::new_readonly(game.key(), true)];
+    let mut account_infos = vec![game_info];
+    for account in remaining_accounts {
+        accounts.push(if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        });
+        account_infos.push(account.clone());
+    }
+    account_infos.push(consumer_program.to_account_info());
+
+    let instruction = Instruction {
+        program_id: game.consumer_program,
+        accounts,
+        data: encode_roll_callback(callback)?,
+    };
+    invoke_signed(
+        &instruction,
+        &account_infos,
+        &[&[
+            GAME_SEED,
+            game.authority.as_ref(),
+            &game.game_id.to_le_bytes(),
+            &[game.bump],
+        ]],
+    )?;
+    Ok(())
+}
+
+fn emit_game_settled(
+    game: &GameState,
+    game_key: Pubkey,
+    request_id: [u8; 32],
+    slot: u64,
+    retry_count: u8,
+) {
+    emit!(GameSettled {
+        game: game_key,
+        request_id,
+        slot,
+        result: game.result,
+        outcome: game.last_outcome,
+        point: game.point,
+        retry_count,
+    });
+}
+
+fn reveal_payload(
+    randomness: &GameRandomness,
+    request_id: [u8; 32],
+    secret: &[u8; 32],
+    commit_slot: u64,
+    slot_hashes: &[u8],
+) -> Result<Vec<u8>> {
+    require!(
+        hashv(&[secret]).to_bytes() == randomness.commitment,
+        WordGameError::InvalidCommitment
+    );
+    let slot_hash =
+        find_slot_hash(slot_hashes, commit_slot).ok_or(WordGameError::SlotHashUnavailable)?;
+
+    Ok(hashv(&[secret, &slot_hash, &request_id])
+        .to_bytes()
+        .to_vec())
+}
+
+fn find_slot_hash(slot_hashes: &[u8], slot: u64) -> Option<[u8; 32]> {
+    const ENTRY_SIZE: usize = 8 + 32;
+
+    let len = u64::from_le_bytes(slot_hashes.get(..8)?.try_into().ok()?) as usize;
+    slot_hashes
+        .get(8..)?
+        .chunks_exact(ENTRY_SIZE)
+        .take(len)
+        .find(|entry| u64::from_le_bytes(entry[..8].try_into().unwrap()) == slot)
+        .map(|entry| entry[8..].try_into().unwrap())
+}
+
+fn rotate_oracle_authority(
+    game: &mut GameState,
+    randomness: &mut GameRandomness,
+    new_oracle_authority: Pubkey,
+) -> Result<()> {
+    require!(
+        randomness.pending_oracle_authority != Pubkey::default(),
+        WordGameError::NoPendingOracleRotation
+    );
+    require_keys_eq!(
+        new_oracle_authority,
+        randomness.pending_oracle_authority,
+        WordGameError::InvalidOracleAuthority
+    );
+    require!(
+        game.pending_player_requests == 0,
+        WordGameError::RequestPending
+    );
+
+    let index = randomness
+        .oracle_index(&randomness.replaced_oracle_authority)
+        .ok_or(WordGameError::InvalidOracleAuthority)?;
+
+    randomness.oracle_authorities[index] = new_oracle_authority;
+    randomness.pending_oracle_authority = Pubkey::default();
+    randomness.replaced_oracle_authority = Pubkey::default();
+
+    // Oracles play no part in a commit-reveal roll, so rotating one must not
+    // let the player drop an unrevealed request and recover its stake.
+    if game.pending_request_id != [0u8; 32] && randomness.mode == RandomnessMode::Oracle {
+        game.retry_count = game.retry_count.saturating_sub(1);
+        clear_pending_request(game, randomness)?;
+    }
+
+    Ok(())
+}
+
+pub fn oracle_fulfillment_message(
+    game: &Pubkey,
+    request_id: &[u8; 32],
+    randomness_bytes: &[u8],
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(32 + 32 + randomness_bytes.len());
+    message.extend_from_slice(game.as_ref());
+    message.extend_from_slice(request_id);
+    message.extend_from_slice(randomness_bytes);
+    message
+}
+
+fn verify_oracle_signature_instruction(
+    ix: &Instruction,
+    oracle: &Pubkey,
+    expected_message: &[u8],
+) -> Result<()> {
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        WordGameError::MissingOracleSignature
+    );
+    require!(
+        ix.accounts.is_empty(),
+        WordGameError::InvalidOracleSignature
+    );
+
+    let data = &ix.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE && data[0] == 1,
+        WordGameError::InvalidOracleSignature
+    );
+
+    let offsets = &data[ED25519_OFFSETS_START..ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE];
+    let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+    let signature_offset = read_u16(0) as usize;
+    let signature_ix_index = read_u16(2);
+    let public_key_offset = read_u16(4) as usize;
+    let public_key_ix_index = read_u16(6);
+    let message_offset = read_u16(8) as usize;
+    let message_size = read_u16(10) as usize;
+    let message_ix_index = read_u16(12);
+
+    require!(
+        signature_ix_index == u16::MAX
+            && public_key_ix_index == u16::MAX
+            && message_ix_index == u16::MAX,
+        WordGameError::InvalidOracleSignature
+    );
+    require!(
+        data.get(signature_offset..signature_offset + ED25519_SIGNATURE_SIZE)
+            .is_some(),
+        WordGameError::InvalidOracleSignature
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + ED25519_PUBKEY_SIZE)
+        .ok_or(WordGameError::InvalidOracleSignature)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(WordGameError::InvalidOracleSignature)?;
+
+    require!(
+        public_key == oracle.as_ref(),
+        WordGameError::InvalidOracleSignature
+    );
+    require!(
+        message == expected_message,
+        WordGameError::InvalidOracleSignature
+    );
+    Ok(())
+}
+
+fn configure_oracle_set(
+    randomness: &mut GameRandomness,
+    oracle_authorities: &[Pubkey],
+    oracle_threshold: u8,
+) -> Result<()> {
+    require!(
+        !oracle_authorities.is_empty() && oracle_authorities.len() <= MAX_ORACLES,
+        WordGameError::InvalidOracleSet
+    );
+    require!(
+        oracle_threshold > 0 && oracle_threshold as usize <= oracle_authorities.len(),
+        WordGameError::InvalidOracleThreshold
+    );
+    for (index, oracle) in oracle_authorities.iter().enumerate() {
+        require!(
+            *oracle != Pubkey::default() && !oracle_authorities[..index].contains(oracle),
+            WordGameError::InvalidOracleSet
+        );
+    }
+
+    randomness.oracle_authorities = [Pubkey::default(); MAX_ORACLES];
+    randomness.oracle_authorities[..oracle_authorities.len()].copy_from_slice(oracle_authorities);
+    randomness.oracle_count = oracle_authorities.len() as u8;
+    randomness.oracle_threshold = oracle_threshold;
+    randomness.pending_oracle_authority = Pubkey::default();
+    randomness.replaced_oracle_authority = Pubkey::default();
+    clear_oracle_contributions(randomness);
+
+    Ok(())
+}
+
+pub fn oracle_commitment(request_id: &[u8; 32], randomness_bytes: &[u8]) -> [u8; 32] {
+    hashv(&[request_id, randomness_bytes]).to_bytes()
+}
+
+fn record_oracle_commitment(
+    round: &mut OracleRound,
+    threshold: u8,
+    oracle_index: usize,
+    commitment: [u8; 32],
+) -> Result<()> {
+    require!(threshold > 1, WordGameError::OracleCommitmentNotExpected);
+    require!(commitment != [0u8; 32], WordGameError::InvalidCommitment);
+    require!(
+        !round.committed[oracle_index],
+        WordGameError::DuplicateOracleContribution
+    );
+    require!(
+        round.commitment_count < threshold,
+        WordGameError::OracleCommitPhaseClosed
+    );
+
+    round.commitments[oracle_index] = commitment;
+    round.committed[oracle_index] = true;
+    round.commitment_count += 1;
+    Ok(())
+}
+
+fn record_oracle_contribution(
+    round: &mut OracleRound,
+    threshold: u8,
+    oracle_index: usize,
+    request_id: [u8; 32],
+    randomness_bytes: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    require!(
+        !round.contributed[oracle_index],
+        WordGameError::DuplicateOracleContribution
+    );
+
+    if threshold == 1 {
+        return Ok(Some(randomness_bytes.to_vec()));
+    }
+
+    // Every contributor is bound to bytes committed before any reveal, so the
+    // last revealer can no longer grind against the earlier reveals.
+    require!(
+        round.commitment_count >= threshold,
+        WordGameError::OracleCommitPhaseOpen
+    );
+    require!(
+        round.committed[oracle_index],
+        WordGameError::MissingOracleCommitment
+    );
+    require!(
+        oracle_commitment(&request_id, randomness_bytes) == round.commitments[oracle_index],
+        WordGameError::InvalidOracleReveal
+    );
+
+    round.contribution_hashes[oracle_index] = hashv(&[randomness_bytes]).to_bytes();
+    round.contributed[oracle_index] = true;
+    round.contribution_count = round.contribution_count.saturating_add(1);
+
+    if round.contribution_count < threshold {
+        return Ok(None);
+    }
+
+    let mut preimage: Vec<&[u8]> = vec![&request_id];
+    for (contributed, hash) in round.contributed.iter().zip(&round.contribution_hashes) {
+        if *contributed {
+            preimage.push(hash);
+        }
+    }
+
+    Ok(Some(hashv(&preimage).to_bytes().to_vec()))
+}
+
+fn clear_oracle_contributions(randomness: &mut GameRandomness) {
+    randomness.oracle_round = OracleRound::default();
+}
+
+fn clear_pending_request(game: &mut GameState, randomness: &mut GameRandomness) -> Result<()> {
+    refund_pending_stake(game)?;
+    game.pending_request_id = [0u8; 32];
+    game.pending_request_slot = 0;
+    randomness.last_request_id = [0u8; 32];
+    randomness.commitment = [0u8; 32];
+    clear_oracle_contributions(randomness);
+    Ok(())
+}
+
+fn is_request_expired(game: &GameState, slot: u64, paused_slots: u64) -> bool {
+    slot > game
+        .pending_request_slot
+        .saturating_add(game.request_expiry_slots)
+        .saturating_add(paused_slots)
+}
+
+fn cancel_expired_request(
+    game: &mut GameState,
+    randomness: &mut GameRandomness,
+    slot: u64,
+    paused_slots: u64,
+) -> Result<()> {
+    require!(
+        game.pending_request_id != [0u8; 32],
+        WordGameError::NoPendingRequest
+    );
+    require!(
+        is_request_expired(game, slot, paused_slots),
+        WordGameError::RequestNotExpired
+    );
+
+    let request_id = game.pending_request_id;
+    if randomness.mode == RandomnessMode::CommitReveal {
+        game.pending_stake = 0;
+    }
+    clear_pending_request(game, randomness)?;
+
+    if randomness.mode == RandomnessMode::CommitReveal {
+        game.result = GameResult::Lost;
+        game.settled = true;
+        game.status = GameStatus::Settled;
+    } else if game.retry_count >= game.max_retries {
+        game.result = GameResult::Voided;
+        game.settled = true;
+        game.status = GameStatus::Settled;
+    }
+
+    if game.settled {
+        emit_game_settled(game, randomness.game, request_id, slot, game.retry_count);
+    }
+
+    Ok(())
+}
+
+fn ensure_closable(game: &GameState) -> Result<()> {
+    require!(
+        game.settled || game.pending_request_id == [0u8; 32],
+        WordGameError::RequestPending
+    );
+    require!(
+        game.player_liabilities == 0,
+        WordGameError::PlayerFundsOutstanding
+    );
+    Ok(())
+}
+
+// Does not read the game account, so players can recover their rent after
+// close_game; a closed game never leaves player funds behind.
+fn ensure_player_closable(player_state: &PlayerState) -> Result<()> {
+    require!(
+        player_state.pending_request_id == [0u8; 32],
+        WordGameError::RequestPending
+    );
+    require!(
+        player_state.claimable_lamports == 0,
+        WordGameError::PlayerFundsOutstanding
+    );
+    Ok(())
+}
+
+fn reset_for_new_game(game: &mut GameState, randomness: &mut GameRandomness) -> Result<()> {
+    game.completed_games = game
+        .completed_games
+        .checked_add(1)
+        .ok_or(WordGameError::MathOverflow)?;
+    game.archived_outcome = game.last_outcome;
+    game.archived_result = game.result;
+
+    game.status = GameStatus::ReadyToRoll;
+    game.settled = false;
+    game.retry_count = 0;
+    game.last_outcome = 0;
+    game.last_die_faces = [0u8; 2];
+    game.point = 0;
+    game.result = GameResult::Pending;
+    clear_pending_request(game, randomness)?;
+
+    Ok(())
+}
+
+fn apply_roll_outcome(game: &mut GameState, outcome: u8) {
+    let result = match game.point {
+        0 => match outcome {
+            7 | 11 => GameResult::Won,
+            2 | 3 | 12 => GameResult::Lost,
+            point => {
+                game.point = point;
+                GameResult::Pending
+            }
+        },
+        point if outcome == point => GameResult::Won,
+        _ if outcome == 7 => GameResult::Lost,
+        _ => GameResult::Pending,
+    };
+
+    if result == GameResult::Pending {
+        game.status = GameStatus::PointEstablished;
+    } else {
+        game.result = result;
+        game.settled = true;
+        game.status = GameStatus::Settled;
+    }
+}
+
+pub struct DiceSample {
+    pub faces: [u8; 2],
+    pub rejected_bytes: u64,
+}
+
+pub fn sample_dice(randomness_bytes: &[u8]) -> Result<DiceSample> {
+    let mut cursor = RandomnessCursor::new(randomness_bytes);
+    let mut faces = [0u8; 2];
+    for face in &mut faces {
+        *face = cursor
+            .next_in_range(1, 6, SampleWidth::U8)
+            .map_err(sampling_error)? as u8;
+    }
+    let accepted_bytes = faces.len() * SampleWidth::U8.bytes();
+    Ok(DiceSample {
+        faces,
+        rejected_bytes: (cursor.bytes_consumed() - accepted_bytes) as u64,
+    })
+}
+
+pub fn rejection_sample_die_faces(randomness_bytes: &[u8]) -> Result<[u8; 2]> {
+    Ok(sample_dice(randomness_bytes)?.faces)
+}
+
+fn sampling_error(err: SamplingError) -> Error {
+    match err {
+        SamplingError::InsufficientEntropy => {
+            error!(WordGameError::InsufficientRandomnessEntropy)
+        }
+        SamplingError::InvalidRange | SamplingError::RangeExceedsWidth => {
+            error!(WordGameError::InvalidSampleRange)
+        }
+    }
+}
+
+pub fn rejection_sample_sum_2_to_12(randomness_bytes: &[u8]) -> Result<u8> {
+    let faces = rejection_sample_die_faces(randomness_bytes)?;
+    Ok(faces[0] + faces[1])
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    ReadyToRoll,
+    PointEstablished,
+    Settled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct InitializeGameParams {
+    pub request_cooldown: i64,
+    pub cooldown_unit: CooldownUnit,
+    pub max_retries: u8,
+    pub request_expiry_slots: u64,
+    pub payout_table_bps: [u32; DICE_OUTCOMES],
+    pub bankroll_lamports: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CooldownUnit {
+    Seconds,
+    Slots,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RandomnessMode {
+    Oracle,
+    CommitReveal,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Pending,
+    Won,
+    Lost,
+    Voided,
+}
+
+#[account]
+pub struct GameState {
+    pub authority: Pubkey,
+    pub randomness_account: Pubkey,
+    pub status: GameStatus,
+    pub settled: bool,
+    pub last_request_timestamp: i64,
+    pub request_cooldown: i64,
+    pub retry_count: u8,
+    pub max_retries: u8,
+    pub last_callback_slot: u64,
+    pub pending_request_id: [u8; 32],
+    pub last_outcome: u8,
+    pub point: u8,
+    pub result: GameResult,
+    pub last_die_faces: [u8; 2],
+    pub archived_outcome: u8,
+    pub archived_result: GameResult,
+    pub completed_games: u64,
+    pub request_expiry_slots: u64,
+    pub pending_request_slot: u64,
+    pub request_nonce: u64,
+    pub game_id: u64,
+    pub bump: u8,
+    pub payout_table_bps: [u32; DICE_OUTCOMES],
+    pub pending_stake: u64,
+    pub claimable_lamports: u64,
+    pub cooldown_unit: CooldownUnit,
+    pub last_request_slot: u64,
+    pub paused: bool,
+    pub consumer_program: Pubkey,
+    pub player_cooldown: i64,
+    pub player_request_window: i64,
+    pub max_player_requests_per_window: u32,
+    pub pause_ledger: PauseLedger,
+    pub request_paused_snapshot: u64,
+    pub player_liabilities: u64,
+    pub pending_player_requests: u32,
+    pub pending_callback: Option<RollCallback>,
+}
+
+impl GameState {
+    pub const SPACE: usize = 8
+        + 32
+        + 32
+        + 1
+        + 1
+        + 8
+        + 8
+        + 1
+        + 1
+        + 8
+        + 32
+        + 1
+        + 1
+        + 1
+        + 2
+        + 1
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 4 * DICE_OUTCOMES
+        + 8
+        + 8
+        + 1
+        + 8
+        + 1
+        + 32
+        + 8
+        + 8
+        + 4
+        + PauseLedger::SPACE
+        + 8
+        + 8
+        + 4
+        + 1
+        + RollCallback::SPACE;
+}
+
+#[account]
+pub struct GameRandomness {
+    pub authority: Pubkey,
+    pub game: Pubkey,
+    pub oracle_authorities: [Pubkey; MAX_ORACLES],
+    pub oracle_count: u8,
+    pub oracle_threshold: u8,
+    pub last_request_id: [u8; 32],
+    pub last_fulfilled_request_id: [u8; 32],
+    pub last_callback_slot: u64,
+    pub pending_oracle_authority: Pubkey,
+    pub replaced_oracle_authority: Pubkey,
+    pub mode: RandomnessMode,
+    pub commitment: [u8; 32],
+    pub bump: u8,
+    pub roll_history: [RollRecord; ROLL_HISTORY_LEN],
+    pub roll_history_head: u8,
+    pub roll_history_len: u8,
+    pub outcome_counts: [u64; DICE_OUTCOMES],
+    pub rejected_bytes: u64,
+    pub oracle_round: OracleRound,
+}
+
+impl GameRandomness {
+    pub const SPACE: usize = 8
+        + 32
+        + 32
+        + 32 * MAX_ORACLES
+        + 1
+        + 1
+        + 32
+        + 32
+        + 8
+        + 32
+        + 32
+        + 1
+        + 32
+        + 1
+        + RollRecord::SPACE * ROLL_HISTORY_LEN
+        + 1
+        + 1
+        + 8 * DICE_OUTCOMES
+        + 8
+        + OracleRound::SPACE;
+
+    pub fn oracle_index(&self, oracle: &Pubkey) -> Option<usize> {
+        self.oracle_authorities
+            .iter()
+            .take(self.oracle_count as usize)
+            .position(|authority| authority == oracle)
+    }
+
+    pub fn record_roll(&mut self, record: RollRecord) {
+        let head = self.roll_history_head as usize;
+        self.roll_history[head] = record;
+        self.roll_history_head = ((head + 1) % ROLL_HISTORY_LEN) as u8;
+        if (self.roll_history_len as usize) < ROLL_HISTORY_LEN {
+            self.roll_history_len += 1;
+        }
+    }
+
+    pub fn record_outcome(&mut self, outcome: u8, rejected_bytes: u64) {
+        let count = &mut self.outcome_counts[(outcome - 2) as usize];
+        *count = count.saturating_add(1);
+        self.rejected_bytes = self.rejected_bytes.saturating_add(rejected_bytes);
+    }
+
+    pub fn recent_rolls(&self) -> Vec<RollRecord> {
+        let len = self.roll_history_len as usize;
+        let start = (self.roll_history_head as usize + ROLL_HISTORY_LEN - len) % ROLL_HISTORY_LEN;
+        (0..len)
+            .map(|offset| self.roll_history[(start + offset) % ROLL_HISTORY_LEN])
+            .collect()
+    }
+}
+
+#[account]
+pub struct AdminConfig {
+    pub admin: Pubkey,
+    pub guardian: Pubkey,
+    pub paused: bool,
+    pub bump: u8,
+    pub pause_ledger: PauseLedger,
+}
+
+impl AdminConfig {
+    pub const SPACE: usize = 8 + 32 + 32 + 1 + 1 + PauseLedger::SPACE;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PauseLedger {
+    pub paused_at_slot: u64,
+    pub total_paused_slots: u64,
+}
+
+impl PauseLedger {
+    pub const SPACE: usize = 8 + 8;
+
+    pub fn record_change(&mut self, was_paused: bool, paused: bool, slot: u64) {
+        if paused && !was_paused {
+            self.paused_at_slot = slot;
+        } else if was_paused && !paused {
+            self.total_paused_slots = self
+                .total_paused_slots
+                .saturating_add(slot.saturating_sub(self.paused_at_slot));
+        }
+    }
+
+    pub fn paused_slots(&self, paused: bool, slot: u64) -> u64 {
+        let ongoing = if paused {
+            slot.saturating_sub(self.paused_at_slot)
+        } else {
+            0
+        };
+        self.total_paused_slots.saturating_add(ongoing)
+    }
+}
+
+#[account]
+pub struct PlayerState {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub last_request_at: i64,
+    pub window_start: i64,
+    pub window_request_count: u32,
+    pub total_requests: u64,
+    pub bump: u8,
+    pub pending_request_id: [u8; 32],
+    pub pending_request_slot: u64,
+    pub request_paused_snapshot: u64,
+    pub pending_stake: u64,
+    pub reserved_payout: u64,
+    pub oracle_round: OracleRound,
+    pub last_die_faces: [u8; 2],
+    pub last_outcome: u8,
+    pub last_payout_lamports: u64,
+    pub claimable_lamports: u64,
+}
+
+impl PlayerState {
+    pub const SPACE: usize =
+        8 + 32 + 32 + 8 + 8 + 4 + 8 + 1 + 32 + 8 + 8 + 8 + 8 + OracleRound::SPACE + 2 + 1 + 8 + 8;
+}
+
+#[account]
+pub struct GameVault {
+    pub game: Pubkey,
+    pub bump: u8,
+}
+
+impl GameVault {
+    pub const SPACE: usize = 8 + 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RollRecord {
+    pub request_id: [u8; 32],
+    pub request_slot: u64,
+    pub fulfilled_slot: u64,
+    pub randomness_hash: [u8; 32],
+    pub outcome: u8,
+}
+
+impl RollRecord {
+    pub const SPACE: usize = 32 + 8 + 8 + 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OracleRound {
+    pub commitments: [[u8; 32]; MAX_ORACLES],
+    pub committed: [bool; MAX_ORACLES],
+    pub commitment_count: u8,
+    pub contribution_hashes: [[u8; 32]; MAX_ORACLES],
+    pub contributed: [bool; MAX_ORACLES],
+    pub contribution_count: u8,
+}
+
+impl OracleRound {
+    pub const SPACE: usize = 2 * (32 * MAX_ORACLES + MAX_ORACLES + 1);
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct InitializeGame<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Program-side pubkey validation only.
+    pub oracle_authority: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [GAME_SEED, authority.key().as_ref(), &game_id.to_le_bytes()],
+        bump,
+        space = GameState::SPACE
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump,
+        space = GameRandomness::SPACE
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+    #[account(
+        init,
+        payer = authority,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump,
+        space = GameVault::SPACE
+    )]
+    pub vault: Account<'info, GameVault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRoll<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = vault.bump,
+        has_one = game
+    )]
+    pub vault: Account<'info, GameVault>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
 
 #[derive(Accounts)]
-pub struct InitializeGame<'info> {
+pub struct RequestRollCpi<'info> {
+    pub authority: Signer<'info>,
     #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = vault.bump,
+        has_one = game
+    )]
+    pub vault: Account<'info, GameVault>,
+    pub system_program: Program<'info, System>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+#[derive(Accounts)]
+pub struct StartNewGame<'info> {
     pub authority: Signer<'info>,
-    /// CHECK: Program-side pubkey validation only.
-    pub oracle_authority: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+}
+
+#[derive(Accounts)]
+pub struct CloseGame<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = vault.bump,
+        has_one = game
+    )]
+    pub vault: Account<'info, GameVault>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAdmin<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
     #[account(
         init,
-        payer = authority,
-        space = GameState::SPACE
+        payer = admin,
+        seeds = [ADMIN_SEED],
+        bump,
+        space = AdminConfig::SPACE
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ WordGameError::InvalidAuthority
+    )]
+    pub program: Program<'info, program::WordGameAnchor>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ WordGameError::InvalidAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [ADMIN_SEED], bump = admin_config.bump, has_one = admin)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    pub signer: Signer<'info>,
+    #[account(mut, seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetGamePaused<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+}
+
+#[derive(Accounts)]
+pub struct SetConsumerProgram<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+}
+
+#[derive(Accounts)]
+pub struct DeliverRollCallback<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameState>,
+    /// CHECK: Must be the consumer program registered on the game.
+    #[account(address = game.consumer_program @ WordGameError::ConsumerProgramMismatch)]
+    pub consumer_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPlayerLimits<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterPlayer<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
     )]
     pub game: Account<'info, GameState>,
     #[account(
         init,
-        payer = authority,
-        space = GameRandomness::SPACE
+        payer = player,
+        seeds = [PLAYER_SEED, game.key().as_ref(), player.key().as_ref()],
+        bump,
+        space = PlayerState::SPACE
+    )]
+    pub player_state: Account<'info, PlayerState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestRollAsPlayer<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PLAYER_SEED, game.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump,
+        has_one = game,
+        has_one = player
+    )]
+    pub player_state: Account<'info, PlayerState>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
     )]
     pub randomness: Account<'info, GameRandomness>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = vault.bump,
+        has_one = game
+    )]
+    pub vault: Account<'info, GameVault>,
     pub system_program: Program<'info, System>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
 }
 
 #[derive(Accounts)]
-pub struct RequestRoll<'info> {
+pub struct CancelPlayerRequest<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [PLAYER_SEED, game.key().as_ref(), player_state.player.as_ref()],
+        bump = player_state.bump,
+        has_one = game
+    )]
+    pub player_state: Account<'info, PlayerState>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPlayerWinnings<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
-    #[account(mut, has_one = authority)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PLAYER_SEED, game.key().as_ref(), player.key().as_ref()],
+        bump = player_state.bump,
+        has_one = game,
+        has_one = player
+    )]
+    pub player_state: Account<'info, PlayerState>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
     pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = vault.bump,
+        has_one = game
+    )]
+    pub vault: Account<'info, GameVault>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePlayer<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+    #[account(
+        mut,
+        close = player,
+        seeds = [PLAYER_SEED, player_state.game.as_ref(), player.key().as_ref()],
+        bump = player_state.bump,
+        has_one = player
+    )]
+    pub player_state: Account<'info, PlayerState>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
     #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump = vault.bump,
+        has_one = game
+    )]
+    pub vault: Account<'info, GameVault>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPendingRequest<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeOracleAuthority<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOracleAuthority<'info> {
+    pub new_oracle_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+}
+
+#[derive(Accounts)]
+pub struct RevealRoll<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+    /// CHECK: Address is constrained to the slot hashes sysvar.
+    #[account(address = sysvar::slot_hashes::ID)]
+    pub recent_slot_hashes: UncheckedAccount<'info>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ForfeitUnrevealedRoll<'info> {
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+#[derive(Accounts)]
+pub struct SetRandomnessMode<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+}
+
+#[derive(Accounts)]
+pub struct SetOracleSet<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = authority
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
+    pub randomness: Account<'info, GameRandomness>,
+}
+
+#[derive(Accounts)]
+pub struct CommitOracleRandomness<'info> {
+    pub oracle_authority: Signer<'info>,
+    #[account(
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameState>,
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
     pub randomness: Account<'info, GameRandomness>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+    #[account(
+        mut,
+        seeds = [PLAYER_SEED, game.key().as_ref(), player_state.player.as_ref()],
+        bump = player_state.bump,
+        has_one = game
+    )]
+    pub player_state: Option<Account<'info, PlayerState>>,
 }
 
 #[derive(Accounts)]
 pub struct ConsumeRandomnessCallback<'info> {
     #[account(mut)]
     pub oracle_authority: Signer<'info>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [GAME_SEED, game.authority.as_ref(), &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
     pub game: Account<'info, GameState>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [RANDOMNESS_SEED, game.key().as_ref()],
+        bump = randomness.bump,
+        has_one = game
+    )]
     pub randomness: Account<'info, GameRandomness>,
+    /// CHECK: Address is constrained to the instructions sysvar.
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    #[account(seeds = [ADMIN_SEED], bump = admin_config.bump)]
+    pub admin_config: Account<'info, AdminConfig>,
+    #[account(
+        mut,
+        seeds = [PLAYER_SEED, game.key().as_ref(), player_state.player.as_ref()],
+        bump = player_state.bump,
+        has_one = game
+    )]
+    pub player_state: Option<Account<'info, PlayerState>>,
+}
+
+#[event]
+pub struct RollRequested {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub request_id: [u8; 32],
+    pub request_nonce: u64,
+    pub slot: u64,
+    pub retry_count: u8,
+    pub stake_lamports: u64,
+}
+
+#[event]
+pub struct RandomnessFulfilled {
+    pub game: Pubkey,
+    pub request_id: [u8; 32],
+    pub slot: u64,
+    pub randomness_hash: [u8; 32],
+    pub die_faces: [u8; 2],
+    pub outcome: u8,
+    pub retry_count: u8,
+    pub payout_lamports: u64,
+}
+
+#[event]
+pub struct GameSettled {
+    pub game: Pubkey,
+    pub request_id: [u8; 32],
+    pub slot: u64,
+    pub result: GameResult,
+    pub outcome: u8,
+    pub point: u8,
+    pub retry_count: u8,
 }
 
 #[error_code]
@@ -294,40 +2810,258 @@ pub enum WordGameError {
     InvalidCooldown,
     #[msg("Invalid retry limit")]
     InvalidRetryLimit,
+    #[msg("Sample range is invalid for the requested width")]
+    InvalidSampleRange,
+    #[msg("The game has not been settled yet")]
+    GameNotSettled,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Invalid request expiry")]
+    InvalidRequestExpiry,
+    #[msg("A randomness request is already pending")]
+    RequestPending,
+    #[msg("No randomness request is pending")]
+    NoPendingRequest,
+    #[msg("The pending randomness request has expired")]
+    RequestExpired,
+    #[msg("The pending randomness request has not expired yet")]
+    RequestNotExpired,
+    #[msg("No oracle authority rotation is pending")]
+    NoPendingOracleRotation,
+    #[msg("Oracle set must hold between one and five distinct keys")]
+    InvalidOracleSet,
+    #[msg("Oracle threshold must be between one and the oracle count")]
+    InvalidOracleThreshold,
+    #[msg("Oracle already contributed to the pending request")]
+    DuplicateOracleContribution,
+    #[msg("Callback must follow an Ed25519 signature instruction")]
+    MissingOracleSignature,
+    #[msg("Ed25519 instruction does not sign this fulfillment with the oracle key")]
+    InvalidOracleSignature,
+    #[msg("Instruction does not match the configured randomness mode")]
+    RandomnessModeMismatch,
+    #[msg("Commitment is missing or does not match the revealed secret")]
+    InvalidCommitment,
+    #[msg("Slot hash for the commit slot is not available")]
+    SlotHashUnavailable,
+    #[msg("Payout table entry exceeds the maximum multiplier")]
+    InvalidPayoutTable,
+    #[msg("Stake could exceed the available bankroll")]
+    StakeExceedsBankroll,
+    #[msg("Nothing to claim")]
+    NothingToClaim,
+    #[msg("Vault cannot cover the claim")]
+    InsufficientBankroll,
+    #[msg("This game is paused")]
+    GamePaused,
+    #[msg("All games are paused")]
+    GloballyPaused,
+    #[msg("Consumer program does not match the registered consumer")]
+    ConsumerProgramMismatch,
+    #[msg("Consumer callback is malformed or was not signed by the game")]
+    InvalidConsumerCallback,
+    #[msg("Invalid per-player rate limit")]
+    InvalidRateLimit,
+    #[msg("This game does not accept player rolls")]
+    PlayerRollsDisabled,
+    #[msg("Player has reached the request limit for this window")]
+    PlayerRateLimited,
+    #[msg("Single-oracle sets do not take commitments")]
+    OracleCommitmentNotExpected,
+    #[msg("Enough oracles have already committed to this request")]
+    OracleCommitPhaseClosed,
+    #[msg("Reveals open once the threshold of oracles has committed")]
+    OracleCommitPhaseOpen,
+    #[msg("Oracle did not commit to this request")]
+    MissingOracleCommitment,
+    #[msg("Revealed randomness does not match the oracle commitment")]
+    InvalidOracleReveal,
+    #[msg("No roll callback is waiting for delivery")]
+    NoPendingCallback,
+    #[msg("Players still have pending rolls or unclaimed winnings")]
+    PlayerFundsOutstanding,
+    #[msg("Deliver the pending roll callback before requesting another roll")]
+    UndeliveredRollCallback,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ed25519_instruction(signer: &Pubkey, message: &[u8]) -> Instruction {
+        let public_key_offset = (ED25519_OFFSETS_START + ED25519_OFFSETS_SIZE) as u16;
+        let signature_offset = public_key_offset + ED25519_PUBKEY_SIZE as u16;
+        let message_offset = signature_offset + ED25519_SIGNATURE_SIZE as u16;
+
+        let mut data = vec![1u8, 0u8];
+        for field in [
+            signature_offset,
+            u16::MAX,
+            public_key_offset,
+            u16::MAX,
+            message_offset,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[0u8; ED25519_SIGNATURE_SIZE]);
+        data.extend_from_slice(message);
+
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    fn ready_game() -> GameState {
+        GameState {
+            authority: Pubkey::new_unique(),
+            randomness_account: Pubkey::new_unique(),
+            status: GameStatus::ReadyToRoll,
+            settled: false,
+            last_request_timestamp: 0,
+            request_cooldown: 0,
+            retry_count: 0,
+            max_retries: 3,
+            last_callback_slot: 0,
+            pending_request_id: [0u8; 32],
+            last_outcome: 0,
+            point: 0,
+            result: GameResult::Pending,
+            last_die_faces: [0u8; 2],
+            archived_outcome: 0,
+            archived_result: GameResult::Pending,
+            completed_games: 0,
+            request_expiry_slots: 20,
+            pending_request_slot: 0,
+            request_nonce: 0,
+            game_id: 0,
+            bump: 255,
+            payout_table_bps: [0; DICE_OUTCOMES],
+            pending_stake: 0,
+            claimable_lamports: 0,
+            cooldown_unit: CooldownUnit::Seconds,
+            last_request_slot: 0,
+            paused: false,
+            consumer_program: Pubkey::default(),
+            player_cooldown: 0,
+            player_request_window: 0,
+            max_player_requests_per_window: 0,
+            pause_ledger: PauseLedger::default(),
+            request_paused_snapshot: 0,
+            player_liabilities: 0,
+            pending_player_requests: 0,
+            pending_callback: None,
+        }
+    }
+
+    fn linked_randomness(game: &GameState) -> GameRandomness {
+        let mut oracle_authorities = [Pubkey::default(); MAX_ORACLES];
+        oracle_authorities[0] = Pubkey::new_unique();
+
+        GameRandomness {
+            authority: game.authority,
+            game: Pubkey::new_unique(),
+            oracle_authorities,
+            oracle_count: 1,
+            oracle_threshold: 1,
+            last_request_id: [0u8; 32],
+            last_fulfilled_request_id: [0u8; 32],
+            last_callback_slot: 0,
+            pending_oracle_authority: Pubkey::default(),
+            replaced_oracle_authority: Pubkey::default(),
+            mode: RandomnessMode::Oracle,
+            commitment: [0u8; 32],
+            bump: 255,
+            roll_history: [RollRecord::default(); ROLL_HISTORY_LEN],
+            roll_history_head: 0,
+            roll_history_len: 0,
+            outcome_counts: [0; DICE_OUTCOMES],
+            rejected_bytes: 0,
+            oracle_round: OracleRound::default(),
+        }
+    }
+
+    fn registered_player(game: Pubkey) -> PlayerState {
+        PlayerState {
+            game,
+            player: Pubkey::new_unique(),
+            last_request_at: 0,
+            window_start: 0,
+            window_request_count: 0,
+            total_requests: 0,
+            bump: 255,
+            pending_request_id: [0u8; 32],
+            pending_request_slot: 0,
+            request_paused_snapshot: 0,
+            pending_stake: 0,
+            reserved_payout: 0,
+            oracle_round: OracleRound::default(),
+            last_die_faces: [0u8; 2],
+            last_outcome: 0,
+            last_payout_lamports: 0,
+            claimable_lamports: 0,
+        }
+    }
 
-    fn map_byte(b: u8) -> Option<u8> {
-        rejection_sample_sum_2_to_12(&[b]).ok()
+    fn slot_hashes_data(entries: &[(u64, [u8; 32])]) -> Vec<u8> {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for (slot, hash) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(hash);
+        }
+        data
     }
 
     #[test]
-    fn rejection_sampling_is_uniform_across_accepted_domain() {
-        let mut counts = [0u16; 11];
+    fn die_faces_are_uniform_across_accepted_domain() {
+        let mut counts = [0u16; 6];
 
-        for b in 0u8..253u8 {
-            let mapped = map_byte(b).expect("bytes under 253 must map");
-            let idx = (mapped - 2) as usize;
-            counts[idx] += 1;
+        for b in 0u8..252u8 {
+            let faces = rejection_sample_die_faces(&[b, b]).expect("bytes under 252 must map");
+            counts[(faces[0] - 1) as usize] += 1;
+            assert_eq!(faces[0], faces[1]);
         }
 
         for count in counts {
-            assert_eq!(count, 23);
+            assert_eq!(count, 42);
+        }
+    }
+
+    #[test]
+    fn dice_sums_follow_two_dice_distribution() {
+        let mut counts = [0u32; 11];
+
+        for first in 0u8..252u8 {
+            for second in 0u8..252u8 {
+                let sum = rejection_sample_sum_2_to_12(&[first, second])
+                    .expect("accepted bytes must map");
+                counts[(sum - 2) as usize] += 1;
+            }
+        }
+
+        for (idx, count) in counts.iter().enumerate() {
+            let sum = idx as u32 + 2;
+            let ways = 6 - sum.abs_diff(7);
+            assert_eq!(*count, ways * 42 * 42);
         }
     }
 
     #[test]
     fn rejection_sampling_uses_next_byte_when_first_is_rejected() {
-        let roll = rejection_sample_sum_2_to_12(&[255, 252]).expect("second byte accepted");
-        assert_eq!(roll, 12);
+        let faces = rejection_sample_die_faces(&[255, 252, 5, 253, 0]).expect("two bytes accepted");
+        assert_eq!(faces, [6, 1]);
+        let roll =
+            rejection_sample_sum_2_to_12(&[255, 252, 5, 253, 0]).expect("two bytes accepted");
+        assert_eq!(roll, 7);
     }
 
     #[test]
-    fn rejection_sampling_fails_when_all_bytes_rejected() {
-        let err = rejection_sample_sum_2_to_12(&[253, 254, 255]).unwrap_err();
+    fn rejection_sampling_fails_without_two_accepted_bytes() {
+        let err = rejection_sample_sum_2_to_12(&[252, 0, 253, 254, 255]).unwrap_err();
         match err {
             Error::AnchorError(anchor_err) => {
                 assert_eq!(
@@ -347,22 +3081,43 @@ mod tests {
             status: GameStatus::PointEstablished,
             settled: false,
             last_request_timestamp: 50,
-            request_cooldown_seconds: 5,
+            request_cooldown: 5,
             retry_count: 1,
             max_retries: 3,
             last_callback_slot: 9,
             pending_request_id: [3u8; 32],
             last_outcome: 0,
+            point: 6,
+            result: GameResult::Pending,
+            last_die_faces: [0u8; 2],
+            archived_outcome: 0,
+            archived_result: GameResult::Pending,
+            completed_games: 0,
+            request_expiry_slots: 20,
+            pending_request_slot: 8,
+            request_nonce: 1,
+            game_id: 0,
+            bump: 255,
+            payout_table_bps: [0; DICE_OUTCOMES],
+            pending_stake: 0,
+            claimable_lamports: 0,
+            cooldown_unit: CooldownUnit::Seconds,
+            last_request_slot: 0,
+            paused: false,
+            consumer_program: Pubkey::default(),
+            player_cooldown: 0,
+            player_request_window: 0,
+            max_player_requests_per_window: 0,
+            pause_ledger: PauseLedger::default(),
+            request_paused_snapshot: 0,
+            player_liabilities: 0,
+            pending_player_requests: 0,
+            pending_callback: None,
         };
 
-        let mut rand = GameRandomness {
-            authority: game.authority,
-            game: Pubkey::new_unique(),
-            oracle_authority: Pubkey::new_unique(),
-            last_request_id: [4u8; 32],
-            last_fulfilled_request_id: [0u8; 32],
-            last_callback_slot: 9,
-        };
+        let mut rand = linked_randomness(&game);
+        rand.last_request_id = [4u8; 32];
+        rand.last_callback_slot = 9;
 
         let validation = (|| -> Result<()> {
             require!(
@@ -378,9 +3133,1063 @@ mod tests {
         assert!(!game.settled);
         assert_eq!(rand.last_fulfilled_request_id, [0u8; 32]);
     }
+
+    #[test]
+    fn come_out_roll_settles_naturals_and_craps() {
+        for (outcome, expected) in [
+            (7, GameResult::Won),
+            (11, GameResult::Won),
+            (2, GameResult::Lost),
+            (3, GameResult::Lost),
+            (12, GameResult::Lost),
+        ] {
+            let mut game = ready_game();
+            apply_roll_outcome(&mut game, outcome);
+            assert!(game.settled);
+            assert!(game.status == GameStatus::Settled);
+            assert!(game.result == expected);
+            assert_eq!(game.point, 0);
+        }
+    }
+
+    #[test]
+    fn point_phase_resolves_on_point_or_seven() {
+        let mut game = ready_game();
+        apply_roll_outcome(&mut game, 6);
+        assert!(game.status == GameStatus::PointEstablished);
+        assert_eq!(game.point, 6);
+        assert!(!game.settled);
+
+        apply_roll_outcome(&mut game, 11);
+        assert!(game.status == GameStatus::PointEstablished);
+        assert!(game.result == GameResult::Pending);
+
+        apply_roll_outcome(&mut game, 6);
+        assert!(game.status == GameStatus::Settled);
+        assert!(game.result == GameResult::Won);
+
+        let mut game = ready_game();
+        apply_roll_outcome(&mut game, 9);
+        apply_roll_outcome(&mut game, 7);
+        assert!(game.status == GameStatus::Settled);
+        assert!(game.result == GameResult::Lost);
+        assert_eq!(game.point, 9);
+    }
+
+    #[test]
+    fn account_space_covers_serialized_state() {
+        let mut game = ready_game();
+        game.pending_callback = Some(roll_callback(&game, Pubkey::new_unique(), [1u8; 32], 1));
+        assert_eq!(GameState::SPACE, 8 + game.try_to_vec().unwrap().len());
+    }
+
+    #[test]
+    fn new_game_archives_outcome_and_keeps_slot_guards() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        apply_roll_outcome(&mut game, 11);
+        game.last_outcome = 11;
+        game.last_callback_slot = 42;
+        game.retry_count = 2;
+        rand.last_request_id = [7u8; 32];
+        rand.last_fulfilled_request_id = [7u8; 32];
+        rand.last_callback_slot = 42;
+
+        reset_for_new_game(&mut game, &mut rand).expect("reset must succeed");
+
+        assert!(game.status == GameStatus::ReadyToRoll);
+        assert!(!game.settled);
+        assert_eq!(game.retry_count, 0);
+        assert_eq!(game.pending_request_id, [0u8; 32]);
+        assert_eq!(game.point, 0);
+        assert!(game.result == GameResult::Pending);
+        assert_eq!(game.archived_outcome, 11);
+        assert!(game.archived_result == GameResult::Won);
+        assert_eq!(game.completed_games, 1);
+        assert_eq!(game.last_callback_slot, 42);
+        assert_eq!(rand.last_callback_slot, 42);
+        assert_eq!(rand.last_request_id, [0u8; 32]);
+        assert_eq!(rand.last_fulfilled_request_id, [7u8; 32]);
+    }
+
+    #[test]
+    fn cancel_requires_an_expired_pending_request() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+
+        let err = cancel_expired_request(&mut game, &mut rand, 100, 0).unwrap_err();
+        assert_eq!(err, error!(WordGameError::NoPendingRequest));
+
+        game.pending_request_id = [5u8; 32];
+        game.pending_request_slot = 100;
+        game.retry_count = 1;
+        rand.last_request_id = [5u8; 32];
+        assert!(!is_request_expired(&game, 120, 0));
+        assert!(cancel_expired_request(&mut game, &mut rand, 120, 0).is_err());
+        assert_eq!(game.pending_request_id, [5u8; 32]);
+
+        assert!(is_request_expired(&game, 121, 0));
+        cancel_expired_request(&mut game, &mut rand, 121, 0).expect("expired request cancels");
+        assert_eq!(game.pending_request_id, [0u8; 32]);
+        assert_eq!(rand.last_request_id, [0u8; 32]);
+        assert!(!game.settled);
+        assert_eq!(game.retry_count, 1);
+    }
+
+    #[test]
+    fn cancel_voids_game_once_retries_are_exhausted() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        game.pending_request_id = [5u8; 32];
+        game.pending_request_slot = 10;
+        game.retry_count = game.max_retries;
+        rand.last_request_id = [5u8; 32];
+
+        cancel_expired_request(&mut game, &mut rand, 31, 0).expect("expired request cancels");
+        assert!(game.settled);
+        assert!(game.status == GameStatus::Settled);
+        assert!(game.result == GameResult::Voided);
+
+        reset_for_new_game(&mut game, &mut rand).expect("voided game can restart");
+        assert!(game.archived_result == GameResult::Voided);
+        assert_eq!(game.retry_count, 0);
+    }
+
+    #[test]
+    fn oracle_rotation_requires_the_proposed_key_and_drops_in_flight_requests() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let old_oracle = rand.oracle_authorities[0];
+        let new_oracle = Pubkey::new_unique();
+
+        assert!(rotate_oracle_authority(&mut game, &mut rand, new_oracle).is_err());
+
+        rand.replaced_oracle_authority = old_oracle;
+        rand.pending_oracle_authority = new_oracle;
+        game.pending_request_id = [8u8; 32];
+        game.pending_request_slot = 40;
+        game.retry_count = 2;
+        rand.last_request_id = [8u8; 32];
+
+        assert!(rotate_oracle_authority(&mut game, &mut rand, Pubkey::new_unique()).is_err());
+        assert_eq!(rand.oracle_authorities[0], old_oracle);
+
+        rotate_oracle_authority(&mut game, &mut rand, new_oracle).expect("rotation must succeed");
+        assert_eq!(rand.oracle_authorities[0], new_oracle);
+        assert_eq!(rand.oracle_index(&old_oracle), None);
+        assert_eq!(rand.pending_oracle_authority, Pubkey::default());
+        assert_eq!(game.pending_request_id, [0u8; 32]);
+        assert_eq!(rand.last_request_id, [0u8; 32]);
+        assert_eq!(game.retry_count, 1);
+    }
+
+    #[test]
+    fn oracle_rotation_waits_for_pending_player_requests() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let game_key = Pubkey::new_unique();
+        let old_oracle = rand.oracle_authorities[0];
+        let new_oracle = Pubkey::new_unique();
+        rand.replaced_oracle_authority = old_oracle;
+        rand.pending_oracle_authority = new_oracle;
+        let mut player = registered_player(game_key);
+        let clock = Clock {
+            slot: 100,
+            ..Clock::default()
+        };
+        open_player_request(&mut game, game_key, &mut player, &clock).expect("request opens");
+        assert!(ensure_player_closable(&player).is_err());
+
+        assert_eq!(
+            rotate_oracle_authority(&mut game, &mut rand, new_oracle).unwrap_err(),
+            error!(WordGameError::RequestPending)
+        );
+        assert_eq!(rand.oracle_authorities[0], old_oracle);
+
+        cancel_expired_player_request(&mut game, &mut player, 121, 0)
+            .expect("expired request clears");
+        assert_eq!(game.pending_player_requests, 0);
+        rotate_oracle_authority(&mut game, &mut rand, new_oracle).expect("rotation must succeed");
+        assert_eq!(rand.oracle_authorities[0], new_oracle);
+    }
+
+    #[test]
+    fn oracle_rotation_leaves_commit_reveal_requests_to_be_revealed_or_forfeited() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let old_oracle = rand.oracle_authorities[0];
+        let new_oracle = Pubkey::new_unique();
+        rand.mode = RandomnessMode::CommitReveal;
+        rand.commitment = [4u8; 32];
+        rand.replaced_oracle_authority = old_oracle;
+        rand.pending_oracle_authority = new_oracle;
+        game.pending_request_id = [8u8; 32];
+        game.pending_request_slot = 40;
+        game.pending_stake = 300;
+        game.retry_count = 1;
+        rand.last_request_id = [8u8; 32];
+
+        rotate_oracle_authority(&mut game, &mut rand, new_oracle).expect("rotation must succeed");
+        assert_eq!(rand.oracle_authorities[0], new_oracle);
+        assert_eq!(game.pending_request_id, [8u8; 32]);
+        assert_eq!(rand.commitment, [4u8; 32]);
+        assert_eq!(game.pending_stake, 300);
+        assert_eq!(game.claimable_lamports, 0);
+        assert_eq!(game.retry_count, 1);
+
+        cancel_expired_request(&mut game, &mut rand, 61, 0).expect("unrevealed roll forfeits");
+        assert!(game.result == GameResult::Lost);
+        assert_eq!(game.claimable_lamports, 0);
+    }
+
+    #[test]
+    fn randomness_space_covers_serialized_state() {
+        let rand = linked_randomness(&ready_game());
+        assert_eq!(GameRandomness::SPACE, 8 + rand.try_to_vec().unwrap().len());
+    }
+
+    #[test]
+    fn oracle_set_rejects_duplicates_and_bad_thresholds() {
+        let mut rand = linked_randomness(&ready_game());
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        assert!(configure_oracle_set(&mut rand, &[], 1).is_err());
+        assert!(configure_oracle_set(&mut rand, &[a, a], 1).is_err());
+        assert!(configure_oracle_set(&mut rand, &[a, b], 3).is_err());
+        assert!(configure_oracle_set(&mut rand, &[a, b], 0).is_err());
+        assert!(
+            configure_oracle_set(&mut rand, &[Pubkey::new_unique(); MAX_ORACLES + 1], 1).is_err()
+        );
+
+        configure_oracle_set(&mut rand, &[a, b], 2).expect("valid set");
+        assert_eq!(rand.oracle_count, 2);
+        assert_eq!(rand.oracle_index(&b), Some(1));
+    }
+
+    #[test]
+    fn threshold_aggregation_waits_for_k_distinct_oracles() {
+        let mut rand = linked_randomness(&ready_game());
+        let oracles = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        configure_oracle_set(&mut rand, &oracles, 2).expect("valid set");
+        let request_id = [6u8; 32];
+
+        record_oracle_commitment(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            2,
+            oracle_commitment(&request_id, &[1, 2, 3]),
+        )
+        .expect("first commitment");
+        record_oracle_commitment(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            0,
+            oracle_commitment(&request_id, &[4, 5, 6]),
+        )
+        .expect("second commitment");
+
+        let first = record_oracle_contribution(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            2,
+            request_id,
+            &[1, 2, 3],
+        )
+        .unwrap();
+        assert!(first.is_none());
+        assert!(record_oracle_contribution(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            2,
+            request_id,
+            &[9]
+        )
+        .is_err());
+
+        let payload = record_oracle_contribution(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            0,
+            request_id,
+            &[4, 5, 6],
+        )
+        .unwrap()
+        .expect("threshold reached");
+        let expected = hashv(&[
+            &request_id,
+            &hashv(&[&[4, 5, 6]]).to_bytes(),
+            &hashv(&[&[1, 2, 3]]).to_bytes(),
+        ]);
+        assert_eq!(payload, expected.to_bytes().to_vec());
+
+        clear_oracle_contributions(&mut rand);
+        record_oracle_commitment(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            2,
+            oracle_commitment(&request_id, &[1, 2, 3]),
+        )
+        .expect("first commitment");
+        record_oracle_commitment(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            0,
+            oracle_commitment(&request_id, &[4, 5, 7]),
+        )
+        .expect("second commitment");
+        let other = record_oracle_contribution(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            2,
+            request_id,
+            &[1, 2, 3],
+        )
+        .unwrap();
+        assert!(other.is_none());
+        let changed = record_oracle_contribution(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            0,
+            request_id,
+            &[4, 5, 7],
+        )
+        .unwrap()
+        .expect("threshold reached");
+        assert_ne!(changed, payload);
+    }
+
+    #[test]
+    fn oracle_signature_must_bind_game_request_and_bytes() {
+        let oracle = Pubkey::new_unique();
+        let game = Pubkey::new_unique();
+        let message = oracle_fulfillment_message(&game, &[3u8; 32], &[1, 2, 3]);
+
+        let ix = ed25519_instruction(&oracle, &message);
+        verify_oracle_signature_instruction(&ix, &oracle, &message).expect("signature matches");
+
+        let other_request = oracle_fulfillment_message(&game, &[4u8; 32], &[1, 2, 3]);
+        assert!(verify_oracle_signature_instruction(&ix, &oracle, &other_request).is_err());
+        let other_bytes = oracle_fulfillment_message(&game, &[3u8; 32], &[1, 2, 4]);
+        assert!(verify_oracle_signature_instruction(&ix, &oracle, &other_bytes).is_err());
+        assert!(verify_oracle_signature_instruction(&ix, &Pubkey::new_unique(), &message).is_err());
+
+        let mut foreign = ix.clone();
+        foreign.program_id = Pubkey::new_unique();
+        assert!(verify_oracle_signature_instruction(&foreign, &oracle, &message).is_err());
+
+        let mut cross_ix = ix.clone();
+        cross_ix.data[ED25519_OFFSETS_START + 6..ED25519_OFFSETS_START + 8]
+            .copy_from_slice(&0u16.to_le_bytes());
+        assert!(verify_oracle_signature_instruction(&cross_ix, &oracle, &message).is_err());
+    }
+
+    #[test]
+    fn reveal_combines_secret_with_commit_slot_hash() {
+        let mut rand = linked_randomness(&ready_game());
+        rand.mode = RandomnessMode::CommitReveal;
+        let secret = [42u8; 32];
+        rand.commitment = hashv(&[&secret]).to_bytes();
+        let request_id = [9u8; 32];
+        let slot_hashes = slot_hashes_data(&[(101, [1u8; 32]), (100, [2u8; 32])]);
+
+        let payload = reveal_payload(&rand, request_id, &secret, 100, &slot_hashes)
+            .expect("reveal must match commitment");
+        assert_eq!(
+            payload,
+            hashv(&[&secret, &[2u8; 32], &request_id])
+                .to_bytes()
+                .to_vec()
+        );
+        assert!(rejection_sample_sum_2_to_12(&payload).is_ok());
+
+        assert!(reveal_payload(&rand, request_id, &[41u8; 32], 100, &slot_hashes).is_err());
+        assert!(reveal_payload(&rand, request_id, &secret, 99, &slot_hashes).is_err());
+    }
+
+    #[test]
+    fn expired_commitment_forfeits_the_game() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        rand.mode = RandomnessMode::CommitReveal;
+        rand.commitment = [1u8; 32];
+        game.pending_request_id = [5u8; 32];
+        game.pending_request_slot = 10;
+        game.retry_count = 1;
+        rand.last_request_id = [5u8; 32];
+
+        cancel_expired_request(&mut game, &mut rand, 31, 0).expect("expired request cancels");
+        assert!(game.settled);
+        assert!(game.result == GameResult::Lost);
+        assert_eq!(rand.commitment, [0u8; 32]);
+    }
+
+    #[test]
+    fn request_ids_are_derived_from_game_nonce_slot_and_authority() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let game_key = Pubkey::new_unique();
+        let authority = game.authority;
+        let clock = Clock {
+            slot: 77,
+            unix_timestamp: 1_000,
+            ..Clock::default()
+        };
+
+        let first = open_roll_request(&mut game, game_key, &mut rand, authority, &clock)
+            .expect("first request opens");
+        assert_eq!(first, derive_request_id(&game_key, 0, 77, &authority));
+        assert_eq!(game.pending_request_id, first);
+        assert_eq!(rand.last_request_id, first);
+        assert_eq!(game.request_nonce, 1);
+
+        game.pending_request_id = [0u8; 32];
+        let second = open_roll_request(&mut game, game_key, &mut rand, authority, &clock)
+            .expect("second request opens");
+        assert_ne!(first, second);
+        assert_eq!(second, derive_request_id(&game_key, 1, 77, &authority));
+        assert_ne!(
+            second,
+            derive_request_id(&game_key, 1, 77, &Pubkey::new_unique())
+        );
+    }
+
+    #[test]
+    fn undelivered_callbacks_block_the_next_roll_request() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let game_key = Pubkey::new_unique();
+        let authority = game.authority;
+        let clock = Clock {
+            slot: 77,
+            unix_timestamp: 1_000,
+            ..Clock::default()
+        };
+        game.consumer_program = Pubkey::new_unique();
+        game.pending_callback = Some(roll_callback(&game, game_key, [3u8; 32], 70));
+
+        assert_eq!(
+            open_roll_request(&mut game, game_key, &mut rand, authority, &clock).unwrap_err(),
+            error!(WordGameError::UndeliveredRollCallback)
+        );
+        assert_eq!(game.pending_request_id, [0u8; 32]);
+        assert_eq!(game.request_nonce, 0);
+
+        take_pending_callback(&mut game).expect("callback is delivered");
+        open_roll_request(&mut game, game_key, &mut rand, authority, &clock)
+            .expect("request opens once the callback is delivered");
+    }
+
+    #[test]
+    fn game_and_randomness_addresses_are_rederivable() {
+        let authority = Pubkey::new_unique();
+        let (game, game_bump) = find_game_address(&authority, 7);
+        let (randomness, randomness_bump) = find_randomness_address(&game);
+
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[
+                    GAME_SEED,
+                    authority.as_ref(),
+                    &7u64.to_le_bytes(),
+                    &[game_bump]
+                ],
+                &crate::ID,
+            )
+            .unwrap(),
+            game
+        );
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[RANDOMNESS_SEED, game.as_ref(), &[randomness_bump]],
+                &crate::ID,
+            )
+            .unwrap(),
+            randomness
+        );
+        assert_ne!(find_game_address(&authority, 8).0, game);
+    }
+
+    #[test]
+    fn close_is_blocked_only_by_an_unsettled_pending_request() {
+        let mut game = ready_game();
+        ensure_closable(&game).expect("idle game closes");
+
+        game.pending_request_id = [5u8; 32];
+        assert_eq!(
+            ensure_closable(&game).unwrap_err(),
+            error!(WordGameError::RequestPending)
+        );
+
+        game.settled = true;
+        game.status = GameStatus::Settled;
+        ensure_closable(&game).expect("settled game closes");
+    }
+
+    #[test]
+    fn roll_history_keeps_the_most_recent_rolls_in_order() {
+        let mut rand = linked_randomness(&ready_game());
+        assert!(rand.recent_rolls().is_empty());
+
+        for roll in 0..(ROLL_HISTORY_LEN as u64 + 3) {
+            rand.record_roll(RollRecord {
+                request_id: [roll as u8 + 1; 32],
+                request_slot: roll * 10,
+                fulfilled_slot: roll * 10 + 2,
+                randomness_hash: [roll as u8; 32],
+                outcome: (roll % 11) as u8 + 2,
+            });
+        }
+
+        let rolls = rand.recent_rolls();
+        assert_eq!(rolls.len(), ROLL_HISTORY_LEN);
+        let slots: Vec<u64> = rolls.iter().map(|record| record.request_slot).collect();
+        let expected: Vec<u64> = (3..ROLL_HISTORY_LEN as u64 + 3)
+            .map(|roll| roll * 10)
+            .collect();
+        assert_eq!(slots, expected);
+    }
+
+    #[test]
+    fn fulfillment_appends_a_history_record() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        game.pending_request_id = [9u8; 32];
+        game.pending_request_slot = 40;
+        rand.last_request_id = [9u8; 32];
+
+        let payload = [0u8, 1u8];
+        fulfill_roll(&mut game, &mut rand, [9u8; 32], 45, &payload).expect("roll fulfills");
+
+        let rolls = rand.recent_rolls();
+        assert_eq!(rolls.len(), 1);
+        assert!(
+            rolls[0]
+                == RollRecord {
+                    request_id: [9u8; 32],
+                    request_slot: 40,
+                    fulfilled_slot: 45,
+                    randomness_hash: hashv(&[&payload]).to_bytes(),
+                    outcome: 3,
+                }
+        );
+    }
+
+    #[test]
+    fn stakes_are_capped_by_the_bankroll_and_paid_from_the_table() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        game.payout_table_bps = [0; DICE_OUTCOMES];
+        game.payout_table_bps[3 - 2] = 50_000;
+        game.claimable_lamports = 100;
+
+        let bankroll = available_bankroll(&game, 1_500, 100);
+        assert_eq!(bankroll, 1_300);
+        assert_eq!(
+            place_wager(&mut game, 400, bankroll).unwrap_err(),
+            error!(WordGameError::StakeExceedsBankroll)
+        );
+        assert_eq!(game.pending_stake, 0);
+        place_wager(&mut game, 300, bankroll).expect("stake is covered");
+
+        game.pending_request_id = [9u8; 32];
+        rand.last_request_id = [9u8; 32];
+        fulfill_roll(&mut game, &mut rand, [9u8; 32], 45, &[0u8, 1u8]).expect("roll fulfills");
+        assert_eq!(game.last_outcome, 3);
+        assert_eq!(game.pending_stake, 0);
+        assert_eq!(game.claimable_lamports, 100 + 1_500);
+
+        assert_eq!(
+            take_claimable(&mut game, 1_500, 100).unwrap_err(),
+            error!(WordGameError::InsufficientBankroll)
+        );
+        assert_eq!(take_claimable(&mut game, 1_800, 100).unwrap(), 1_600);
+        assert_eq!(game.claimable_lamports, 0);
+        assert_eq!(
+            take_claimable(&mut game, 1_800, 100).unwrap_err(),
+            error!(WordGameError::NothingToClaim)
+        );
+    }
+
+    #[test]
+    fn expired_oracle_requests_refund_the_stake_but_forfeits_keep_it() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        game.pending_request_id = [5u8; 32];
+        game.pending_request_slot = 100;
+        game.retry_count = 1;
+        game.pending_stake = 250;
+        rand.last_request_id = [5u8; 32];
+
+        cancel_expired_request(&mut game, &mut rand, 121, 0).expect("expired request cancels");
+        assert_eq!(game.pending_stake, 0);
+        assert_eq!(game.claimable_lamports, 250);
+
+        rand.mode = RandomnessMode::CommitReveal;
+        game.pending_request_id = [6u8; 32];
+        game.pending_request_slot = 200;
+        game.pending_stake = 250;
+        rand.last_request_id = [6u8; 32];
+
+        cancel_expired_request(&mut game, &mut rand, 221, 0).expect("unrevealed roll forfeits");
+        assert_eq!(game.pending_stake, 0);
+        assert_eq!(game.claimable_lamports, 250);
+        assert!(game.result == GameResult::Lost);
+    }
+
+    #[test]
+    fn cooldown_is_measured_in_the_configured_unit() {
+        let mut game = ready_game();
+        game.request_cooldown = 10;
+        assert!(cooldown_elapsed(&game, 0, 0));
+
+        game.last_request_timestamp = 1_000;
+        game.last_request_slot = 500;
+        assert!(!cooldown_elapsed(&game, 1_009, 10_000));
+        assert!(cooldown_elapsed(&game, 1_010, 500));
+
+        game.cooldown_unit = CooldownUnit::Slots;
+        assert!(!cooldown_elapsed(&game, 10_000, 509));
+        assert!(cooldown_elapsed(&game, 1_000, 510));
+    }
+
+    #[test]
+    fn pauses_block_rolls_and_only_the_admin_can_resume() {
+        let mut game = ready_game();
+        let mut admin_config = AdminConfig {
+            admin: Pubkey::new_unique(),
+            guardian: Pubkey::new_unique(),
+            paused: false,
+            bump: 255,
+            pause_ledger: PauseLedger::default(),
+        };
+        ensure_not_paused(&game, &admin_config).expect("nothing is paused");
+
+        game.paused = true;
+        assert_eq!(
+            ensure_not_paused(&game, &admin_config).unwrap_err(),
+            error!(WordGameError::GamePaused)
+        );
+        game.paused = false;
+
+        authorize_pause_change(&admin_config, admin_config.guardian, true)
+            .expect("guardian pauses");
+        admin_config.paused = true;
+        assert_eq!(
+            ensure_not_paused(&game, &admin_config).unwrap_err(),
+            error!(WordGameError::GloballyPaused)
+        );
+
+        assert_eq!(
+            authorize_pause_change(&admin_config, admin_config.guardian, false).unwrap_err(),
+            error!(WordGameError::InvalidAuthority)
+        );
+        assert_eq!(
+            authorize_pause_change(&admin_config, Pubkey::new_unique(), true).unwrap_err(),
+            error!(WordGameError::InvalidAuthority)
+        );
+        authorize_pause_change(&admin_config, admin_config.admin, false).expect("admin resumes");
+    }
+
+    #[test]
+    fn consumer_callbacks_round_trip_through_the_published_encoding() {
+        let mut game = ready_game();
+        let game_key = Pubkey::new_unique();
+        game.last_die_faces = [3, 4];
+        game.last_outcome = 7;
+        game.result = GameResult::Won;
+        game.settled = true;
+
+        let callback = roll_callback(&game, game_key, [8u8; 32], 77);
+        let data = encode_roll_callback(&callback).unwrap();
+        assert_eq!(data[..8], consumer::roll_callback_discriminator());
+        assert!(consumer::decode_roll_callback(&data).unwrap() == callback);
+
+        let mut tampered = data.clone();
+        tampered[0] ^= 1;
+        assert!(matches!(
+            consumer::decode_roll_callback(&tampered),
+            Err(err) if err == error!(WordGameError::InvalidConsumerCallback)
+        ));
+    }
+
+    #[test]
+    fn a_failing_consumer_cannot_undo_a_losing_settlement() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        game.consumer_program = Pubkey::new_unique();
+        game.pending_request_id = [9u8; 32];
+        game.pending_request_slot = 40;
+        game.pending_stake = 300;
+        rand.last_request_id = [9u8; 32];
+
+        fulfill_roll(&mut game, &mut rand, [9u8; 32], 45, &[0u8, 1u8]).expect("roll fulfills");
+        assert!(game.settled);
+        assert!(game.result == GameResult::Lost);
+        assert_eq!(game.pending_stake, 0);
+        assert_eq!(game.claimable_lamports, 0);
+        let queued = game.pending_callback.expect("callback is queued");
+        assert!(queued == roll_callback(&game, rand.game, [9u8; 32], 45));
+
+        // A consumer that rejects the callback reverts only the delivery, so the
+        // settled roll stays settled and the stake cannot be recovered by expiry.
+        assert_eq!(
+            cancel_expired_request(&mut game, &mut rand, 500, 0).unwrap_err(),
+            error!(WordGameError::NoPendingRequest)
+        );
+        assert!(take_pending_callback(&mut game).unwrap() == queued);
+        assert!(matches!(
+            take_pending_callback(&mut game),
+            Err(err) if err == error!(WordGameError::NoPendingCallback)
+        ));
+    }
+
+    #[test]
+    fn fulfillments_feed_the_outcome_histogram_and_rejection_count() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let payload = [255, 252, 5, 253, 0];
+        assert_eq!(sample_dice(&payload).unwrap().rejected_bytes, 3);
+
+        game.pending_request_id = [9u8; 32];
+        rand.last_request_id = [9u8; 32];
+        fulfill_roll(&mut game, &mut rand, [9u8; 32], 45, &payload).expect("roll fulfills");
+
+        let mut expected = [0u64; DICE_OUTCOMES];
+        expected[7 - 2] = 1;
+        assert_eq!(rand.outcome_counts, expected);
+        assert_eq!(rand.rejected_bytes, 3);
+    }
+
+    #[test]
+    fn player_requests_respect_cooldown_and_window_limits() {
+        let mut game = ready_game();
+        game.cooldown_unit = CooldownUnit::Slots;
+        let mut player = registered_player(Pubkey::new_unique());
+        let at_slot = |slot: u64| Clock {
+            slot,
+            ..Clock::default()
+        };
+
+        assert_eq!(
+            admit_player_request(&game, &mut player, &at_slot(100)).unwrap_err(),
+            error!(WordGameError::PlayerRollsDisabled)
+        );
+
+        game.player_cooldown = 5;
+        game.player_request_window = 100;
+        game.max_player_requests_per_window = 2;
+
+        admit_player_request(&game, &mut player, &at_slot(100)).expect("first request");
+        assert_eq!(
+            admit_player_request(&game, &mut player, &at_slot(104)).unwrap_err(),
+            error!(WordGameError::CooldownActive)
+        );
+        admit_player_request(&game, &mut player, &at_slot(105)).expect("second request");
+        assert_eq!(
+            admit_player_request(&game, &mut player, &at_slot(150)).unwrap_err(),
+            error!(WordGameError::PlayerRateLimited)
+        );
+        assert_eq!(player.window_request_count, 2);
+
+        admit_player_request(&game, &mut player, &at_slot(200)).expect("new window");
+        assert_eq!(player.window_start, 200);
+        assert_eq!(player.window_request_count, 1);
+        assert_eq!(player.total_requests, 3);
+    }
+
+    #[test]
+    fn player_rolls_hold_their_own_pending_request_and_outcome() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let game_key = Pubkey::new_unique();
+        game.payout_table_bps = [0; DICE_OUTCOMES];
+        game.payout_table_bps[3 - 2] = 50_000;
+        game.pending_request_id = [6u8; 32];
+        let mut alice = registered_player(game_key);
+        let mut bob = registered_player(game_key);
+        let at_slot = |slot: u64| Clock {
+            slot,
+            ..Clock::default()
+        };
+
+        let alice_request = open_player_request(&mut game, game_key, &mut alice, &at_slot(100))
+            .expect("the game's own request does not block players");
+        let bob_request = open_player_request(&mut game, game_key, &mut bob, &at_slot(100))
+            .expect("players do not block each other");
+        assert_ne!(alice_request, bob_request);
+        assert_eq!(game.pending_player_requests, 2);
+        assert_eq!(
+            open_player_request(&mut game, game_key, &mut alice, &at_slot(101)).unwrap_err(),
+            error!(WordGameError::RequestPending)
+        );
+
+        place_player_wager(&mut game, &mut alice, 200, 2_000).expect("stake is covered");
+        place_player_wager(&mut game, &mut bob, 100, 2_000).expect("stake is covered");
+        assert_eq!(game.player_liabilities, 1_000 + 500);
+
+        assert_eq!(
+            validate_player_fulfillment(&game, &alice, bob_request, 105, 0).unwrap_err(),
+            error!(WordGameError::RequestCorrelationMismatch)
+        );
+        validate_player_fulfillment(&game, &alice, alice_request, 105, 0).expect("request matches");
+        fulfill_player_roll(
+            &mut game,
+            &mut rand,
+            &mut alice,
+            alice_request,
+            105,
+            &[0u8, 1u8],
+        )
+        .expect("roll fulfills");
+        assert_eq!(alice.last_outcome, 3);
+        assert_eq!(alice.last_payout_lamports, 1_000);
+        assert_eq!(alice.claimable_lamports, 1_000);
+        assert_eq!(alice.pending_request_id, [0u8; 32]);
+        assert_eq!(bob.pending_request_id, bob_request);
+        assert_eq!(game.pending_request_id, [6u8; 32]);
+        assert_eq!(game.player_liabilities, 1_000 + 500);
+        assert_eq!(game.pending_player_requests, 1);
+        assert_eq!(rand.recent_rolls()[0].request_id, alice_request);
+
+        assert_eq!(
+            cancel_expired_player_request(&mut game, &mut bob, 120, 0).unwrap_err(),
+            error!(WordGameError::RequestNotExpired)
+        );
+        cancel_expired_player_request(&mut game, &mut bob, 121, 0)
+            .expect("expired request refunds");
+        assert_eq!(bob.claimable_lamports, 100);
+        assert_eq!(bob.pending_request_id, [0u8; 32]);
+        assert_eq!(game.player_liabilities, 1_100);
+        assert_eq!(game.pending_player_requests, 0);
+        assert_eq!(
+            ensure_player_closable(&bob).unwrap_err(),
+            error!(WordGameError::PlayerFundsOutstanding)
+        );
+
+        game.pending_request_id = [0u8; 32];
+        assert_eq!(
+            ensure_closable(&game).unwrap_err(),
+            error!(WordGameError::PlayerFundsOutstanding)
+        );
+        assert_eq!(
+            take_player_claimable(&mut game, &mut alice, 5_000, 100).unwrap(),
+            1_000
+        );
+        assert_eq!(
+            take_player_claimable(&mut game, &mut bob, 5_000, 100).unwrap(),
+            100
+        );
+        assert_eq!(game.player_liabilities, 0);
+        ensure_closable(&game).expect("no player funds outstanding");
+        ensure_player_closable(&alice).expect("alice has nothing left to settle");
+        ensure_player_closable(&bob).expect("bob has nothing left to settle");
+    }
+
+    #[test]
+    fn player_stakes_are_reserved_against_the_bankroll() {
+        let mut game = ready_game();
+        game.payout_table_bps = [0; DICE_OUTCOMES];
+        game.payout_table_bps[3 - 2] = 50_000;
+        game.pending_stake = 100;
+        let mut player = registered_player(Pubkey::new_unique());
+
+        assert_eq!(
+            place_player_wager(&mut game, &mut player, 300, 1_300).unwrap_err(),
+            error!(WordGameError::StakeExceedsBankroll)
+        );
+        place_player_wager(&mut game, &mut player, 200, 1_300).expect("stake is covered");
+        assert_eq!(player.reserved_payout, 1_000);
+        assert_eq!(game.player_liabilities, 1_000);
+        assert_eq!(available_bankroll(&game, 2_400, 100), 1_300);
+
+        game.claimable_lamports = 500;
+        assert_eq!(
+            take_claimable(&mut game, 1_400, 100).unwrap_err(),
+            error!(WordGameError::InsufficientBankroll)
+        );
+    }
+
+    #[test]
+    fn player_space_covers_serialized_state() {
+        let player = registered_player(Pubkey::new_unique());
+        assert_eq!(PlayerState::SPACE, 8 + player.try_to_vec().unwrap().len());
+    }
+
+    #[test]
+    fn last_oracle_cannot_steer_the_aggregate_after_seeing_earlier_reveals() {
+        let mut rand = linked_randomness(&ready_game());
+        let oracles = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        configure_oracle_set(&mut rand, &oracles, 2).expect("valid set");
+        let request_id = [6u8; 32];
+
+        record_oracle_commitment(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            0,
+            oracle_commitment(&request_id, &[1, 2, 3]),
+        )
+        .expect("first commitment");
+        assert_eq!(
+            record_oracle_contribution(
+                &mut rand.oracle_round,
+                rand.oracle_threshold,
+                0,
+                request_id,
+                &[1, 2, 3]
+            )
+            .unwrap_err(),
+            error!(WordGameError::OracleCommitPhaseOpen)
+        );
+        record_oracle_commitment(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            1,
+            oracle_commitment(&request_id, &[4, 5, 6]),
+        )
+        .expect("second commitment");
+        assert_eq!(
+            record_oracle_commitment(
+                &mut rand.oracle_round,
+                rand.oracle_threshold,
+                2,
+                oracle_commitment(&request_id, &[7])
+            )
+            .unwrap_err(),
+            error!(WordGameError::OracleCommitPhaseClosed)
+        );
+
+        assert!(record_oracle_contribution(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            0,
+            request_id,
+            &[1, 2, 3]
+        )
+        .unwrap()
+        .is_none());
+
+        // Having seen the first reveal, the last oracle tries every other byte
+        // string; none of them is accepted in place of its commitment.
+        for grind in 0..=u8::MAX {
+            if grind == 4 {
+                continue;
+            }
+            assert_eq!(
+                record_oracle_contribution(
+                    &mut rand.oracle_round,
+                    rand.oracle_threshold,
+                    1,
+                    request_id,
+                    &[grind, 5, 6]
+                )
+                .unwrap_err(),
+                error!(WordGameError::InvalidOracleReveal)
+            );
+        }
+        assert_eq!(
+            record_oracle_contribution(
+                &mut rand.oracle_round,
+                rand.oracle_threshold,
+                2,
+                request_id,
+                &[7]
+            )
+            .unwrap_err(),
+            error!(WordGameError::MissingOracleCommitment)
+        );
+
+        let payload = record_oracle_contribution(
+            &mut rand.oracle_round,
+            rand.oracle_threshold,
+            1,
+            request_id,
+            &[4, 5, 6],
+        )
+        .unwrap()
+        .expect("threshold reached");
+        let expected = hashv(&[
+            &request_id,
+            &hashv(&[&[1, 2, 3]]).to_bytes(),
+            &hashv(&[&[4, 5, 6]]).to_bytes(),
+        ]);
+        assert_eq!(payload, expected.to_bytes().to_vec());
+    }
+
+    #[test]
+    fn pauses_extend_pending_requests_instead_of_refunding_them() {
+        let mut game = ready_game();
+        let mut rand = linked_randomness(&game);
+        let mut admin_config = AdminConfig {
+            admin: Pubkey::new_unique(),
+            guardian: Pubkey::new_unique(),
+            paused: false,
+            bump: 255,
+            pause_ledger: PauseLedger::default(),
+        };
+        rand.mode = RandomnessMode::CommitReveal;
+        game.pending_request_id = [6u8; 32];
+        game.pending_request_slot = 200;
+        game.retry_count = 1;
+        game.pending_stake = 250;
+        game.request_paused_snapshot = paused_slots_total(&game, &admin_config, 200);
+        rand.last_request_id = [6u8; 32];
+
+        game.pause_ledger.record_change(game.paused, true, 210);
+        game.paused = true;
+        assert_eq!(
+            paused_slots_since(&game, &admin_config, game.request_paused_snapshot, 300),
+            90
+        );
+        game.pause_ledger.record_change(game.paused, false, 260);
+        game.paused = false;
+        let paused_slots =
+            paused_slots_since(&game, &admin_config, game.request_paused_snapshot, 270);
+        assert_eq!(paused_slots, 50);
+
+        assert_eq!(
+            cancel_expired_request(&mut game, &mut rand, 270, paused_slots).unwrap_err(),
+            error!(WordGameError::RequestNotExpired)
+        );
+        validate_fulfillment(&game, &rand, [6u8; 32], 270, paused_slots)
+            .expect("the reveal window survives the pause");
+
+        admin_config
+            .pause_ledger
+            .record_change(admin_config.paused, true, 265);
+        admin_config.paused = true;
+        let paused_slots =
+            paused_slots_since(&game, &admin_config, game.request_paused_snapshot, 300);
+        assert_eq!(paused_slots, 50 + 35);
+        assert!(!is_request_expired(&game, 300, paused_slots));
+        admin_config
+            .pause_ledger
+            .record_change(admin_config.paused, false, 280);
+        admin_config.paused = false;
+
+        let paused_slots =
+            paused_slots_since(&game, &admin_config, game.request_paused_snapshot, 286);
+        assert_eq!(paused_slots, 65);
+        cancel_expired_request(&mut game, &mut rand, 286, paused_slots)
+            .expect("unrevealed roll forfeits after the extended expiry");
+        assert!(game.result == GameResult::Lost);
+        assert_eq!(game.retry_count, 1);
+        assert_eq!(game.pending_stake, 0);
+        assert_eq!(game.claimable_lamports, 0);
+    }
 }
 pub mod features {
     pub mod solana_word_game;
 }
 
+pub mod consumer;
+pub mod sampling;
 pub mod types;
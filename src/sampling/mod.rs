@@ -0,0 +1,219 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SamplingError {
+    #[error("sample range is empty")]
+    InvalidRange,
+    #[error("sample range does not fit in the sample width")]
+    RangeExceedsWidth,
+    #[error("randomness payload does not include enough entropy")]
+    InsufficientEntropy,
+}
+
+pub type SamplingResult<T> = Result<T, SamplingError>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleWidth {
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl SampleWidth {
+    pub fn bytes(self) -> usize {
+        match self {
+            SampleWidth::U8 => 1,
+            SampleWidth::U16 => 2,
+            SampleWidth::U32 => 4,
+            SampleWidth::U64 => 8,
+        }
+    }
+
+    fn domain(self) -> u128 {
+        1u128 << (8 * self.bytes())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub value: u64,
+    pub bytes_consumed: usize,
+}
+
+pub fn sample_range(
+    payload: &[u8],
+    lo: u64,
+    hi: u64,
+    width: SampleWidth,
+) -> SamplingResult<Sample> {
+    if lo > hi {
+        return Err(SamplingError::InvalidRange);
+    }
+
+    let span = (hi - lo) as u128 + 1;
+    let domain = width.domain();
+    if span > domain {
+        return Err(SamplingError::RangeExceedsWidth);
+    }
+    let acceptance_bound = domain - (domain % span);
+
+    let mut bytes_consumed = 0;
+    for window in payload.chunks_exact(width.bytes()) {
+        bytes_consumed += window.len();
+
+        let candidate = read_window(window);
+        if candidate < acceptance_bound {
+            return Ok(Sample {
+                value: lo + (candidate % span) as u64,
+                bytes_consumed,
+            });
+        }
+    }
+
+    Err(SamplingError::InsufficientEntropy)
+}
+
+pub fn sample_many(
+    payload: &[u8],
+    lo: u64,
+    hi: u64,
+    width: SampleWidth,
+    count: usize,
+) -> SamplingResult<(Vec<u64>, usize)> {
+    let mut cursor = RandomnessCursor::new(payload);
+    let mut values = Vec::with_capacity(count);
+    for _ in 0..count {
+        values.push(cursor.next_in_range(lo, hi, width)?);
+    }
+    Ok((values, cursor.bytes_consumed()))
+}
+
+pub struct RandomnessCursor<'a> {
+    payload: &'a [u8],
+    bytes_consumed: usize,
+}
+
+impl<'a> RandomnessCursor<'a> {
+    pub fn new(payload: &'a [u8]) -> Self {
+        Self {
+            payload,
+            bytes_consumed: 0,
+        }
+    }
+
+    pub fn bytes_consumed(&self) -> usize {
+        self.bytes_consumed
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.payload.len() - self.bytes_consumed
+    }
+
+    pub fn next_in_range(&mut self, lo: u64, hi: u64, width: SampleWidth) -> SamplingResult<u64> {
+        let sample = sample_range(&self.payload[self.bytes_consumed..], lo, hi, width)?;
+        self.bytes_consumed += sample.bytes_consumed;
+        Ok(sample.value)
+    }
+}
+
+fn read_window(window: &[u8]) -> u128 {
+    window
+        .iter()
+        .rev()
+        .fold(0u128, |acc, &byte| (acc << 8) | byte as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u8_windows_are_unbiased_for_every_span() {
+        for span in 1u64..=256 {
+            let mut counts = vec![0u32; span as usize];
+            let mut rejected = 0u32;
+
+            for byte in 0u8..=255 {
+                match sample_range(&[byte], 10, 10 + span - 1, SampleWidth::U8) {
+                    Ok(sample) => counts[(sample.value - 10) as usize] += 1,
+                    Err(SamplingError::InsufficientEntropy) => rejected += 1,
+                    Err(err) => panic!("unexpected error {err:?}"),
+                }
+            }
+
+            assert_eq!(rejected as u64, 256 % span);
+            assert!(counts.iter().all(|&count| count as u64 == 256 / span));
+        }
+    }
+
+    #[test]
+    fn u16_windows_are_unbiased_for_awkward_spans() {
+        for span in [3u64, 7, 11, 1_000, 65_535, 65_536] {
+            let mut counts = vec![0u32; span as usize];
+            let mut rejected = 0u32;
+
+            for word in 0u16..=u16::MAX {
+                match sample_range(&word.to_le_bytes(), 0, span - 1, SampleWidth::U16) {
+                    Ok(sample) => counts[sample.value as usize] += 1,
+                    Err(SamplingError::InsufficientEntropy) => rejected += 1,
+                    Err(err) => panic!("unexpected error {err:?}"),
+                }
+            }
+
+            assert_eq!(rejected as u64, 65_536 % span);
+            assert!(counts.iter().all(|&count| count as u64 == 65_536 / span));
+        }
+    }
+
+    #[test]
+    fn wide_windows_reject_exactly_the_biased_tail() {
+        let span = 11u64;
+        let bound = (1u128 << 32) - ((1u128 << 32) % span as u128);
+        let last_accepted = ((bound - 1) as u32).to_le_bytes();
+        let first_rejected = (bound as u32).to_le_bytes();
+
+        let sample = sample_range(&last_accepted, 2, 12, SampleWidth::U32).expect("accepted");
+        assert_eq!(sample.value, 2 + ((bound - 1) % span as u128) as u64);
+        assert_eq!(
+            sample_range(&first_rejected, 2, 12, SampleWidth::U32),
+            Err(SamplingError::InsufficientEntropy)
+        );
+
+        let full = sample_range(&u64::MAX.to_le_bytes(), 0, u64::MAX, SampleWidth::U64)
+            .expect("full range never rejects");
+        assert_eq!(full.value, u64::MAX);
+        assert_eq!(full.bytes_consumed, 8);
+    }
+
+    #[test]
+    fn draws_several_values_and_reports_consumed_bytes() {
+        let payload = [255, 0, 254, 5, 9, 1, 2];
+        let (values, consumed) =
+            sample_many(&payload, 1, 6, SampleWidth::U8, 3).expect("three draws must fit");
+        assert_eq!(values, vec![1, 6, 4]);
+        assert_eq!(consumed, 5);
+
+        let mut cursor = RandomnessCursor::new(&payload);
+        assert_eq!(cursor.next_in_range(0, 255, SampleWidth::U16), Ok(255));
+        assert_eq!(cursor.bytes_consumed(), 2);
+        assert_eq!(
+            cursor.next_in_range(0, 10, SampleWidth::U64),
+            Err(SamplingError::InsufficientEntropy)
+        );
+        assert_eq!(cursor.bytes_consumed(), 2);
+        assert_eq!(cursor.remaining(), 5);
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert_eq!(
+            sample_range(&[0], 5, 4, SampleWidth::U8),
+            Err(SamplingError::InvalidRange)
+        );
+        assert_eq!(
+            sample_range(&[0, 0], 0, 256, SampleWidth::U8),
+            Err(SamplingError::RangeExceedsWidth)
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub const MAX_CONTRIBUTORS: usize = 64;
+pub const MAX_BENEFICIARIES: usize = 8;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ContributorRecord {
@@ -6,6 +7,8 @@ pub struct ContributorRecord {
     pub total_contributed_lamports: u64,
     pub claimable_lamports: u64,
     pub claimed_lamports: u64,
+    pub payout_address: [u8; 32],
+    pub claim_delegate: [u8; 32],
     pub is_initialized: bool,
     pub has_claimed_current_round: bool,
 }
@@ -17,12 +20,53 @@ impl ContributorRecord {
             total_contributed_lamports: 0,
             claimable_lamports: 0,
             claimed_lamports: 0,
+            payout_address: [0u8; 32],
+            claim_delegate: [0u8; 32],
             is_initialized: false,
             has_claimed_current_round: false,
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimReceipt {
+    pub contributor: [u8; 32],
+    pub recipient: [u8; 32],
+    pub lamports: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeneficiaryRecord {
+    pub beneficiary: [u8; 32],
+    pub share_bps: u16,
+    pub withdrawable_lamports: u64,
+    pub withdrawn_lamports: u64,
+    pub is_initialized: bool,
+    pub has_withdrawn_current_round: bool,
+}
+
+impl BeneficiaryRecord {
+    pub fn empty() -> Self {
+        Self {
+            beneficiary: [0u8; 32],
+            share_bps: 0,
+            withdrawable_lamports: 0,
+            withdrawn_lamports: 0,
+            is_initialized: false,
+            has_withdrawn_current_round: false,
+        }
+    }
+
+    pub fn new(beneficiary: [u8; 32], share_bps: u16) -> Self {
+        Self {
+            beneficiary,
+            share_bps,
+            is_initialized: true,
+            ..Self::empty()
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RoundState {
     pub round_id: u64,
@@ -48,21 +92,50 @@ impl RoundState {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContributorPayoutPreview {
+    pub contributor: [u8; 32],
+    pub total_contributed_lamports: u64,
+    pub claimable_lamports: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeneficiaryPayoutPreview {
+    pub beneficiary: [u8; 32],
+    pub withdrawable_lamports: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClosePreview {
+    pub round_id: u64,
+    pub total_round_contributions: u64,
+    pub treasury_cut_lamports: u64,
+    pub total_round_claimable: u64,
+    pub dust_lamports: u64,
+    pub contributors: Vec<ContributorPayoutPreview>,
+    pub beneficiaries: Vec<BeneficiaryPayoutPreview>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct WordGameAccount {
     pub owner: [u8; 32],
     pub treasury_bps: u16,
     pub current_round: RoundState,
     pub contributors: Vec<ContributorRecord>,
+    pub beneficiaries: Vec<BeneficiaryRecord>,
 }
 
 impl WordGameAccount {
     pub fn new(owner: [u8; 32], treasury_bps: u16) -> Self {
+        let mut beneficiaries = vec![BeneficiaryRecord::empty(); MAX_BENEFICIARIES];
+        beneficiaries[0] = BeneficiaryRecord::new(owner, 10_000);
+
         Self {
             owner,
             treasury_bps,
             current_round: RoundState::new(1),
             contributors: vec![ContributorRecord::empty(); MAX_CONTRIBUTORS],
+            beneficiaries,
         }
     }
 }